@@ -0,0 +1,164 @@
+use core::future::Future;
+
+use embedded_io::{ErrorType, SliceWriteError};
+
+use crate::Error;
+
+/// Minimal buffered writer with an inline `[u8; N]` scratch
+///
+/// A separate, smaller implementation of the core of [`crate::AwriteBuf`] for
+/// targets where code size matters: `write!()` into the scratch, [`crate::awrite!`]
+/// and the async flush. None of the [`crate::AwriteBuf`] options exist here:
+/// no CRLF translation, overflow policy, line prefix, retries, hooks or
+/// tallies. A full scratch fails with `Error::Sync(SliceWriteError::Full)`
+/// and a failed flush keeps the bytes the sink did not accept, like
+/// [`crate::AwriteBuf`] with its defaults.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, ArrayAwriteBuf, AwriteBuf};
+///
+/// let mut arr = ArrayAwriteBuf::<8, _>::new(Vec::<u8>::new());
+/// let mut gen = AwriteBuf::with_array::<8>(Vec::<u8>::new());
+/// assert_eq!(awrite!(arr, "{}", 1234), awrite!(gen, "{}", 1234));
+/// assert_eq!(awrite!(arr, "too long!"), awrite!(gen, "too long!"));
+/// assert_eq!(arr.written(), gen.written());
+/// assert_eq!(arr.sink_ref(), gen.sink_ref());
+/// # })
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArrayAwriteBuf<const N: usize, U> {
+    buf: [u8; N],
+    pos: usize,
+    sink: U,
+}
+
+impl<const N: usize, U> ArrayAwriteBuf<N, U> {
+    /// Construct with an empty scratch.
+    pub const fn new(sink: U) -> Self {
+        Self {
+            buf: [0; N],
+            pos: 0,
+            sink,
+        }
+    }
+
+    /// Number of buffered bytes.
+    pub fn len(&self) -> usize {
+        self.pos
+    }
+
+    /// No bytes are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    /// Free space in the scratch.
+    pub fn remaining(&self) -> usize {
+        N - self.pos
+    }
+
+    /// The buffered bytes.
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.pos]
+    }
+
+    /// Discard the buffered bytes.
+    pub fn clear(&mut self) {
+        self.pos = 0;
+    }
+
+    /// Borrow the sink.
+    pub fn sink_ref(&self) -> &U {
+        &self.sink
+    }
+
+    /// Borrow the sink mutably.
+    pub fn sink_mut(&mut self) -> &mut U {
+        &mut self.sink
+    }
+
+    /// Return the sink, dropping buffered bytes.
+    pub fn into_sink(self) -> U {
+        self.sink
+    }
+
+    fn push(&mut self, buf: &[u8]) -> usize {
+        let n = buf.len().min(N - self.pos);
+        self.buf[self.pos..][..n].copy_from_slice(&buf[..n]);
+        self.pos += n;
+        n
+    }
+}
+
+impl<const N: usize, U: ErrorType> ArrayAwriteBuf<N, U> {
+    /// Format into the scratch and flush, see [`crate::AwriteBuf::awrite_fmt`].
+    pub fn awrite_fmt(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+    ) -> impl Future<Output = Result<usize, embedded_io::WriteFmtError<Error<U::Error>>>> + '_
+    where
+        U: embedded_io_async::Write,
+    {
+        let ret = embedded_io::Write::write_fmt(self, args);
+        async move {
+            ret?;
+            let len = self.pos;
+            embedded_io_async::Write::flush(self).await?;
+            Ok(len - self.pos)
+        }
+    }
+}
+
+impl<const N: usize, U> core::fmt::Write for ArrayAwriteBuf<N, U> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        if self.push(s.as_bytes()) == s.len() {
+            Ok(())
+        } else {
+            Err(core::fmt::Error)
+        }
+    }
+}
+
+impl<const N: usize, U: ErrorType> ErrorType for ArrayAwriteBuf<N, U> {
+    type Error = Error<U::Error>;
+}
+
+impl<const N: usize, U: ErrorType> embedded_io::Write for ArrayAwriteBuf<N, U> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        match self.push(buf) {
+            0 if !buf.is_empty() => Err(Error::Sync(SliceWriteError::Full)),
+            n => Ok(n),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<const N: usize, U: embedded_io_async::Write> embedded_io_async::Write
+    for ArrayAwriteBuf<N, U>
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        embedded_io::Write::write(self, buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        let mut sent = 0;
+        let ret = loop {
+            if sent == self.pos {
+                break Ok(());
+            }
+            match self.sink.write(&self.buf[sent..self.pos]).await {
+                Ok(0) => panic!("write() returned Ok(0)"),
+                Ok(n) => sent += n,
+                Err(e) => break Err(Error::Async(e)),
+            }
+        };
+        self.buf.copy_within(sent..self.pos, 0);
+        self.pos -= sent;
+        ret?;
+        self.sink.flush().await.map_err(Error::Async)
+    }
+}
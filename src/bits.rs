@@ -0,0 +1,98 @@
+use crate::{AwriteBuf, Error, Overflow, Scratch};
+
+/// MSB-first bit packer into the scratch, see [`AwriteBuf::bit_writer`]
+///
+/// Completed bytes are appended to the scratch as they are, without CRLF
+/// translation. Pending bits are lost unless [`BitWriter::finish`] is called.
+#[derive(Debug)]
+pub struct BitWriter<'a, T, U> {
+    buf: &'a mut AwriteBuf<T, U>,
+    // Fewer than 8 pending bits, right aligned
+    acc: u64,
+    bits: u8,
+}
+
+impl<T, U> AwriteBuf<T, U> {
+    /// Start packing bit fields into the scratch.
+    ///
+    /// Nothing is flushed, flush as usual after [`BitWriter::finish`].
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{aflush, AwriteBuf, Error};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// let mut bits = buf.bit_writer();
+    /// bits.write_bits(0b101, 3)?
+    ///     .write_bits(0xabc, 12)?
+    ///     .write_bits(1, 1)?;
+    /// assert_eq!(bits.write_bits(0, 33).err(), Some(Error::Fmt));
+    /// bits.finish()?;
+    /// buf.bit_writer().write_bits(0x3, 2)?.finish()?;
+    /// aflush!(buf)?;
+    /// assert_eq!(buf.sink_ref(), &[0b1011_0101, 0b0111_1001, 0b1100_0000]);
+    /// # Ok::<(), Error<core::convert::Infallible>>(())
+    /// # }).unwrap()
+    /// ```
+    pub fn bit_writer(&mut self) -> BitWriter<'_, T, U> {
+        BitWriter {
+            buf: self,
+            acc: 0,
+            bits: 0,
+        }
+    }
+}
+
+impl<T: Scratch, U: embedded_io::ErrorType> BitWriter<'_, T, U> {
+    /// Append the low `n` bits of `value`, most significant first.
+    ///
+    /// More than 32 bits fail with `Error::Fmt`. If the completed bytes don't fit,
+    /// this fails with `Error::Sync(SliceWriteError::Full)` and nothing is written,
+    /// the pending bits stay aligned for the next field.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{aflush, AwriteBuf, Error};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 1], Vec::<u8>::new());
+    /// let mut bits = buf.bit_writer();
+    /// bits.write_bits(0xa, 4)?;
+    /// assert!(bits.write_bits(0xbcd, 12).is_err());
+    /// bits.write_bits(0xb, 4)?.finish()?;
+    /// aflush!(buf)?;
+    /// assert_eq!(buf.sink_ref(), &[0xab]);
+    /// # Ok::<(), Error<core::convert::Infallible>>(())
+    /// # }).unwrap()
+    /// ```
+    pub fn write_bits(&mut self, value: u32, n: u8) -> Result<&mut Self, Error<U::Error>> {
+        if n > 32 {
+            return Err(Error::Fmt);
+        }
+        let bytes = (self.bits + n) as usize / 8;
+        self.buf.buf.grow(self.buf.pos + bytes);
+        if self.buf.config.overflow != Overflow::Truncate && self.buf.would_overflow(bytes) {
+            return Err(Error::Sync(embedded_io::SliceWriteError::Full));
+        }
+        let mask = (1u64 << n) - 1;
+        self.acc = (self.acc << n) | (value as u64 & mask);
+        self.bits += n;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.buf
+                .put(&[(self.acc >> self.bits) as u8])
+                .map_err(Error::Sync)?;
+        }
+        self.acc &= (1 << self.bits) - 1;
+        Ok(self)
+    }
+
+    /// Pad the last partial byte with zero bits.
+    ///
+    /// On a byte boundary nothing is added.
+    pub fn finish(&mut self) -> Result<(), Error<U::Error>> {
+        if self.bits > 0 {
+            self.write_bits(0, 8 - self.bits)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use core::ops::{Deref, DerefMut};
+
+use embedded_io::ErrorType;
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// [`AwriteBuf`] whose async writes flush once the high-water mark is reached
+///
+/// A sync `write()` can't flush, so this only applies to the async
+/// `embedded_io_async::Write::write()`: the bytes are buffered like in
+/// [`AwriteBuf`], then the scratch is flushed if [`AwriteBuf::needs_flush`]
+/// reports. A full scratch is flushed before writing. Dereferences to the
+/// buffer for the sync API and the settings, see [`AwriteBuf::set_high_water`].
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::{AwriteBuf, Buffered};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+/// buf.set_high_water(75);
+/// let mut buf = Buffered::new(buf);
+/// buf.write_all(b"0123").await.unwrap();
+/// buf.write_all(b"4567").await.unwrap();
+/// assert!(buf.sink_ref().is_empty());
+/// buf.write_all(b"89ab").await.unwrap();
+/// assert_eq!(buf.sink_ref(), b"0123456789ab");
+/// assert!(buf.is_empty());
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct Buffered<T, U>(AwriteBuf<T, U>);
+
+impl<T, U> Buffered<T, U> {
+    pub fn new(inner: AwriteBuf<T, U>) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> AwriteBuf<T, U> {
+        self.0
+    }
+}
+
+impl<T, U> Deref for Buffered<T, U> {
+    type Target = AwriteBuf<T, U>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, U> DerefMut for Buffered<T, U> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, U: ErrorType> ErrorType for Buffered<T, U> {
+    type Error = Error<U::Error>;
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> embedded_io_async::Write for Buffered<T, U> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if self.0.is_full() {
+            self.0.force_flush().await?;
+        }
+        let written = embedded_io_async::Write::write(&mut self.0, buf).await?;
+        if self.0.needs_flush() {
+            self.0.force_flush().await?;
+        }
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        embedded_io_async::Write::flush(&mut self.0).await
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{AwriteBuf, Overflow};
+
+/// Collect the [`AwriteBuf`] configuration in one place
+///
+/// ```
+/// use awrite::{AwriteBuf, Overflow};
+///
+/// let buf = AwriteBuf::builder()
+///     .scratch([0u8; 64])
+///     .sink(Vec::<u8>::new())
+///     .line_buffered()
+///     .line_prefix(b"  ")
+///     .crlf()
+///     .atomic()
+///     .overflow(Overflow::Truncate)
+///     .flush_sink(false)
+///     .retry(3)
+///     .build();
+/// assert_eq!(buf.capacity(), 64);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct AwriteBufBuilder<T, U> {
+    inner: AwriteBuf<T, U>,
+}
+
+impl AwriteBuf<(), ()> {
+    /// Start an [`AwriteBufBuilder`] with unit scratch and sink.
+    pub fn builder() -> AwriteBufBuilder<(), ()> {
+        AwriteBufBuilder {
+            inner: AwriteBuf::new((), ()),
+        }
+    }
+}
+
+impl<T, U> AwriteBufBuilder<T, U> {
+    pub fn scratch<S>(self, buf: S) -> AwriteBufBuilder<S, U> {
+        AwriteBufBuilder {
+            inner: AwriteBuf {
+                config: self.inner.config,
+                ..AwriteBuf::new(buf, self.inner.sink)
+            },
+        }
+    }
+
+    pub fn sink<V>(self, sink: V) -> AwriteBufBuilder<T, V> {
+        AwriteBufBuilder {
+            inner: self.inner.map_sink(|_| sink),
+        }
+    }
+
+    /// See [`AwriteBuf::set_line_buffered`]
+    pub fn line_buffered(mut self) -> Self {
+        self.inner.set_line_buffered(true);
+        self
+    }
+
+    /// See [`AwriteBuf::set_flush_on`]
+    pub fn flush_on(mut self, byte: Option<u8>) -> Self {
+        self.inner.set_flush_on(byte);
+        self
+    }
+
+    /// See [`AwriteBuf::set_line_prefix`]
+    pub fn line_prefix(mut self, prefix: &'static [u8]) -> Self {
+        self.inner.set_line_prefix(prefix);
+        self
+    }
+
+    /// See [`AwriteBuf::set_indent_unit`]
+    pub fn indent_unit(mut self, unit: usize) -> Self {
+        self.inner.set_indent_unit(unit);
+        self
+    }
+
+    /// See [`AwriteBuf::set_error_sticky`]
+    pub fn error_sticky(mut self) -> Self {
+        self.inner.set_error_sticky(true);
+        self
+    }
+
+    /// See [`AwriteBuf::set_utf8_boundaries`]
+    pub fn utf8_boundaries(mut self) -> Self {
+        self.inner.set_utf8_boundaries(true);
+        self
+    }
+
+    /// See [`AwriteBuf::set_min_flush`]
+    pub fn min_flush(mut self, min: usize) -> Self {
+        self.inner.set_min_flush(min);
+        self
+    }
+
+    /// See [`AwriteBuf::set_high_water`]
+    pub fn high_water(mut self, percent: u8) -> Self {
+        self.inner.set_high_water(percent);
+        self
+    }
+
+    /// See [`AwriteBuf::set_crlf`]
+    pub fn crlf(mut self) -> Self {
+        self.inner.set_crlf(true);
+        self
+    }
+
+    /// See [`AwriteBuf::set_atomic`]
+    pub fn atomic(mut self) -> Self {
+        self.inner.set_atomic(true);
+        self
+    }
+
+    /// See [`AwriteBuf::with_overflow`]
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.inner = self.inner.with_overflow(overflow);
+        self
+    }
+
+    /// See [`AwriteBuf::set_flush_sink`]
+    pub fn flush_sink(mut self, flush_sink: bool) -> Self {
+        self.inner.set_flush_sink(flush_sink);
+        self
+    }
+
+    /// See [`AwriteBuf::with_retry`]
+    pub fn retry(mut self, max: usize) -> Self {
+        self.inner = self.inner.with_retry(max);
+        self
+    }
+
+    pub fn build(self) -> AwriteBuf<T, U> {
+        self.inner
+    }
+}
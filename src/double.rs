@@ -0,0 +1,204 @@
+use core::future::Future;
+use embedded_io::{ErrorType, SliceWriteError, Write};
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Two scratch halves for pipelined flushing
+///
+/// The active half is the scratch. [`AwriteBuf::swap`] hands the filled half to
+/// the sink while formatting continues into the other half.
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct DoubleBuf<const N: usize> {
+    halves: [[u8; N]; 2],
+    front: usize,
+}
+
+impl<const N: usize> Default for DoubleBuf<N> {
+    fn default() -> Self {
+        Self {
+            halves: [[0; N]; 2],
+            front: 0,
+        }
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for DoubleBuf<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.halves[self.front]
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for DoubleBuf<N> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.halves[self.front]
+    }
+}
+
+impl<const N: usize> Scratch for DoubleBuf<N> {}
+
+/// Writer into the fresh half of a [`DoubleBuf`] while the other half flushes
+///
+/// Behaves like `&mut [u8]`: CRLF translation and [`crate::Overflow`] do not apply.
+#[derive(Debug, PartialEq)]
+pub struct Front<'a> {
+    buf: &'a mut [u8],
+    pos: &'a mut usize,
+}
+
+impl ErrorType for Front<'_> {
+    type Error = SliceWriteError;
+}
+
+impl Write for Front<'_> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written = (&mut self.buf[*self.pos..]).write(buf)?;
+        *self.pos += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl core::fmt::Write for Front<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_all(s.as_bytes()).or(Err(core::fmt::Error))
+    }
+}
+
+impl<const N: usize, U: embedded_io_async::Write> AwriteBuf<DoubleBuf<N>, U> {
+    /// Switch halves and flush the filled one while the other fills.
+    ///
+    /// Returns the flush future (owning the sink and the filled half) and a
+    /// [`Front`] writer into the now active, empty half. Drive both concurrently,
+    /// e.g. with `join`. Bytes written to the front stay buffered.
+    ///
+    /// The flush behaves like [`AwriteBuf::force_flush`]: retries, the line prefix
+    /// and indentation, sticky errors, the sink flush and the flush hook apply.
+    /// If it fails, the bytes of the filled half the sink did not accept are
+    /// dropped, [`AwriteBuf::total_flushed`] counts those it did.
+    /// [`HeaderHandle`](crate::HeaderHandle)s into the filled half are invalidated.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::task::Poll;
+    /// use embedded_io::Write;
+    /// use awrite::{AwriteBuf, DoubleBuf};
+    ///
+    /// // Sink that is busy once per write, like an in-flight DMA transfer
+    /// #[derive(Default)]
+    /// struct Dma(Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Dma {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Dma {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         let mut busy = true;
+    ///         core::future::poll_fn(|cx| {
+    ///             if core::mem::take(&mut busy) {
+    ///                 cx.waker().wake_by_ref();
+    ///                 Poll::Pending
+    ///             } else {
+    ///                 Poll::Ready(())
+    ///             }
+    ///         })
+    ///         .await;
+    ///         self.0.extend_from_slice(buf);
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new(DoubleBuf::<8>::default(), Dma::default());
+    /// write!(buf, "first").unwrap();
+    ///
+    /// let (flush, mut front) = buf.swap();
+    /// let (ret, ()) = futures::join!(flush, async {
+    ///     write!(front, "second").unwrap();
+    /// });
+    /// ret.unwrap();
+    ///
+    /// assert_eq!(buf.sink_ref().0, b"first");
+    /// assert_eq!(buf.written(), b"second");
+    /// # })
+    /// ```
+    ///
+    /// A failing sink latches with [`AwriteBuf::set_error_sticky`]:
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::{ErrorKind, Write};
+    /// use awrite::{AwriteBuf, DoubleBuf, Error};
+    ///
+    /// // Accepts three bytes, then fails
+    /// #[derive(Default)]
+    /// struct Broken(Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Broken {
+    ///     type Error = ErrorKind;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Broken {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         let n = buf.len().min(3 - self.0.len());
+    ///         if n == 0 {
+    ///             return Err(ErrorKind::BrokenPipe);
+    ///         }
+    ///         self.0.extend_from_slice(&buf[..n]);
+    ///         Ok(n)
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new(DoubleBuf::<8>::default(), Broken::default());
+    /// buf.set_error_sticky(true);
+    /// write!(buf, "first").unwrap();
+    /// let (flush, _front) = buf.swap();
+    /// assert_eq!(flush.await, Err(Error::Async(ErrorKind::BrokenPipe)));
+    /// assert_eq!(buf.total_flushed(), 3);
+    /// assert!(buf.is_empty());
+    /// let (flush, _front) = buf.swap();
+    /// assert_eq!(flush.await, Err(Error::Latched(ErrorKind::BrokenPipe)));
+    /// assert_eq!(buf.sink_ref().0, b"fir");
+    /// # })
+    /// ```
+    pub fn swap(
+        &mut self,
+    ) -> (
+        impl Future<Output = Result<(), Error<U::Error>>> + '_,
+        Front<'_>,
+    ) {
+        let len = core::mem::replace(&mut self.pos, 0);
+        self.since = None;
+        // Header handles into the filled half must not patch the fresh one
+        self.generation = self.generation.wrapping_add(1);
+        let [a, b] = &mut self.buf.halves;
+        let (filled, fresh) = if self.buf.front == 0 { (a, b) } else { (b, a) };
+        self.buf.front ^= 1;
+        let mut view = AwriteBuf {
+            buf: filled,
+            sink: &mut self.sink,
+            pos: len,
+            total: self.total,
+            generation: 0,
+            since: None,
+            depth: self.depth,
+            latched: self.latched,
+            config: self.config,
+        };
+        let total = &mut self.total;
+        let latched = &mut self.latched;
+        let flush = async move {
+            let ret = view.flush_n(len).await;
+            *total = view.total;
+            *latched = view.latched;
+            ret
+        };
+        let front = Front {
+            buf: fresh,
+            pos: &mut self.pos,
+        };
+        (flush, front)
+    }
+}
@@ -0,0 +1,55 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Borrow of an [`AwriteBuf`] to be finished with [`WriteGuard::flush`], see [`AwriteBuf::guard`]
+///
+/// Dereferences to the buffer to write through it. There is no async `Drop`:
+/// dropping the guard without flushing leaves the bytes buffered.
+#[derive(Debug)]
+#[must_use = "flush the guard, dropping it leaves the bytes buffered"]
+pub struct WriteGuard<'a, T, U>(&'a mut AwriteBuf<T, U>);
+
+impl<T, U> AwriteBuf<T, U> {
+    /// Borrow the buffer until [`WriteGuard::flush`].
+    ///
+    /// Like [`AwriteBuf::scope`] without the closure.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// let mut guard = buf.guard();
+    /// write!(guard, "a").unwrap();
+    /// write!(guard, "b").unwrap();
+    /// guard.flush().await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"ab");
+    /// # })
+    /// ```
+    pub fn guard(&mut self) -> WriteGuard<'_, T, U> {
+        WriteGuard(self)
+    }
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> WriteGuard<'_, T, U> {
+    /// Flush the buffer and release it, see `embedded_io_async::Write::flush()`.
+    pub async fn flush(self) -> Result<(), Error<U::Error>> {
+        embedded_io_async::Write::flush(self.0).await
+    }
+}
+
+impl<T, U> Deref for WriteGuard<'_, T, U> {
+    type Target = AwriteBuf<T, U>;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl<T, U> DerefMut for WriteGuard<'_, T, U> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0
+    }
+}
@@ -0,0 +1,195 @@
+use embedded_io::{Write, WriteFmtError};
+
+use crate::{AwriteBuf, Error, Scratch};
+
+// Escape one byte of a JSON string, see `AwriteBuf::write_json_str`
+pub(crate) fn escape(b: u8) -> ([u8; 6], usize) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut esc = [b'\\', b, b'0', b'0', 0, 0];
+    let len = match b {
+        b'"' | b'\\' => 2,
+        b'\n' | b'\r' | b'\t' => {
+            esc[1] = match b {
+                b'\n' => b'n',
+                b'\r' => b'r',
+                _ => b't',
+            };
+            2
+        }
+        0..0x20 => {
+            esc[1] = b'u';
+            esc[4] = HEX[(b >> 4) as usize];
+            esc[5] = HEX[(b & 0xf) as usize];
+            6
+        }
+        _ => {
+            esc[0] = b;
+            1
+        }
+    };
+    (esc, len)
+}
+
+// Whether a finite float is written with an exponent, at the same magnitudes as
+// JavaScript's `JSON.stringify`, so huge and tiny values stay short
+pub(crate) fn exponent(v: f64) -> bool {
+    let abs = v.abs();
+    abs >= 1e21 || (abs != 0.0 && abs < 1e-6)
+}
+
+/// Values of a [`JsonObject`] field
+pub trait JsonValue {
+    fn write_json<W: Write>(&self, w: &mut W) -> Result<(), WriteFmtError<W::Error>>;
+}
+
+impl<V: JsonValue + ?Sized> JsonValue for &V {
+    fn write_json<W: Write>(&self, w: &mut W) -> Result<(), WriteFmtError<W::Error>> {
+        (**self).write_json(w)
+    }
+}
+
+/// Quoted and escaped like [`AwriteBuf::write_json_str`]
+impl JsonValue for str {
+    fn write_json<W: Write>(&self, w: &mut W) -> Result<(), WriteFmtError<W::Error>> {
+        w.write_all(b"\"").map_err(WriteFmtError::Other)?;
+        for &b in self.as_bytes() {
+            let (esc, len) = escape(b);
+            w.write_all(&esc[..len]).map_err(WriteFmtError::Other)?;
+        }
+        w.write_all(b"\"").map_err(WriteFmtError::Other)
+    }
+}
+
+impl JsonValue for bool {
+    fn write_json<W: Write>(&self, w: &mut W) -> Result<(), WriteFmtError<W::Error>> {
+        w.write_all(if *self { b"true" } else { b"false" })
+            .map_err(WriteFmtError::Other)
+    }
+}
+
+macro_rules! json_display {
+    ($($t:ty),*) => {
+        $(
+            impl JsonValue for $t {
+                fn write_json<W: Write>(&self, w: &mut W) -> Result<(), WriteFmtError<W::Error>> {
+                    write!(w, "{self}")
+                }
+            }
+        )*
+    };
+}
+
+json_display!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! json_float {
+    ($($t:ty),*) => {
+        $(
+            /// Non-finite values are `null`, see [`AwriteBuf::write_json_f64`]
+            impl JsonValue for $t {
+                fn write_json<W: Write>(&self, w: &mut W) -> Result<(), WriteFmtError<W::Error>> {
+                    if !self.is_finite() {
+                        w.write_all(b"null").map_err(WriteFmtError::Other)
+                    } else if exponent(*self as f64) {
+                        write!(w, "{self:e}")
+                    } else {
+                        write!(w, "{self}")
+                    }
+                }
+            }
+        )*
+    };
+}
+
+json_float!(f32, f64);
+
+/// `None` is `null`
+impl<V: JsonValue> JsonValue for Option<V> {
+    fn write_json<W: Write>(&self, w: &mut W) -> Result<(), WriteFmtError<W::Error>> {
+        match self {
+            Some(v) => v.write_json(w),
+            None => w.write_all(b"null").map_err(WriteFmtError::Other),
+        }
+    }
+}
+
+/// Fluent JSON object writer, see [`AwriteBuf::json_object`]
+#[derive(Debug)]
+pub struct JsonObject<'a, T, U> {
+    buf: &'a mut AwriteBuf<T, U>,
+    first: bool,
+}
+
+impl<T, U> AwriteBuf<T, U> {
+    /// Start a JSON object, written into the scratch field by field.
+    ///
+    /// Fields fail with `SliceWriteError::Full` if the scratch fills, nothing is flushed
+    /// before [`JsonObject::end`]. A failed field is rolled back, the object stays valid.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 64], Vec::<u8>::new());
+    /// buf.json_object()
+    ///     .field("level", "info")?
+    ///     .field("code", 42)?
+    ///     .field("eps", 1e-30f32)?
+    ///     .end()
+    ///     .await?;
+    /// buf.json_object().end().await?;
+    /// assert_eq!(buf.sink_ref(), br#"{"level":"info","code":42,"eps":1e-30}{}"#);
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// let mut obj = buf.json_object();
+    /// obj.field("id", 7)?;
+    /// assert!(obj.field("message", "does not fit").is_err());
+    /// obj.end().await?;
+    /// assert_eq!(buf.sink_ref(), br#"{"id":7}"#);
+    /// # Ok::<(), awrite::Error<core::convert::Infallible>>(())
+    /// # }).unwrap()
+    /// ```
+    pub fn json_object(&mut self) -> JsonObject<'_, T, U> {
+        JsonObject {
+            buf: self,
+            first: true,
+        }
+    }
+}
+
+impl<T: Scratch, U: embedded_io::ErrorType> JsonObject<'_, T, U> {
+    /// Append a `"key":value` pair.
+    ///
+    /// On error nothing of the pair stays buffered.
+    pub fn field(
+        &mut self,
+        key: &str,
+        value: impl JsonValue,
+    ) -> Result<&mut Self, Error<U::Error>> {
+        let start = self.buf.pos;
+        if let Err(e) = self.push(key, value) {
+            self.buf.pos = start;
+            return Err(e);
+        }
+        self.first = false;
+        Ok(self)
+    }
+
+    fn push(&mut self, key: &str, value: impl JsonValue) -> Result<(), Error<U::Error>> {
+        self.buf.write_all(if self.first { b"{" } else { b"," })?;
+        key.write_json(self.buf)?;
+        self.buf.write_all(b":")?;
+        value.write_json(self.buf)?;
+        Ok(())
+    }
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> JsonObject<'_, T, U> {
+    /// Close the object and flush.
+    pub async fn end(&mut self) -> Result<(), Error<U::Error>> {
+        if core::mem::take(&mut self.first) {
+            self.buf.write_all(b"{")?;
+        }
+        self.buf.write_all(b"}")?;
+        embedded_io_async::Write::flush(self.buf).await
+    }
+}
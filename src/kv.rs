@@ -0,0 +1,159 @@
+use core::fmt::Display;
+use embedded_io::Write;
+
+use crate::{AwriteBuf, Error, Scratch};
+
+// Escape one byte of a quoted value
+fn escape(b: u8) -> ([u8; 4], usize) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    match b {
+        b'"' | b'\\' => ([b'\\', b, 0, 0], 2),
+        b'\n' => (*b"\\n\0\0", 2),
+        b'\r' => (*b"\\r\0\0", 2),
+        b'\t' => (*b"\\t\0\0", 2),
+        0..0x20 | 0x7f => (
+            [b'\\', b'x', HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]],
+            4,
+        ),
+        _ => ([b, 0, 0, 0], 1),
+    }
+}
+
+/// `key=value` line writer, see [`AwriteBuf::kv_line`]
+#[derive(Debug)]
+pub struct KvLine<'a, T, U> {
+    buf: &'a mut AwriteBuf<T, U>,
+    first: bool,
+}
+
+impl<T, U> AwriteBuf<T, U> {
+    /// Start a line of space separated `key=value` pairs.
+    ///
+    /// Values that are empty or contain a space, `"`, `=` or a control character
+    /// are quoted. Inside, `"` and `\` are escaped by a backslash, `\n`, `\r` and
+    /// `\t` are written as such and other control characters as `\xHH`, so a value
+    /// never splits the line. Keys are written as they are.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.kv_line()
+    ///     .kv("a", 1)
+    ///     .await?
+    ///     .kv("b", "two words")
+    ///     .await?
+    ///     .kv("c", r#"say "hi""#)
+    ///     .await?
+    ///     .end()
+    ///     .await?;
+    /// assert_eq!(
+    ///     buf.sink_ref(),
+    ///     br#"a=1 b="two words" c="say \"hi\""
+    /// "#
+    /// );
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// buf.kv_line().kv("msg", "ok\nlevel=forged\x07").await?.end().await?;
+    /// assert_eq!(buf.sink_ref(), b"msg=\"ok\\nlevel=forged\\x07\"\n");
+    /// # Ok::<(), awrite::Error<core::convert::Infallible>>(())
+    /// # }).unwrap()
+    /// ```
+    pub fn kv_line(&mut self) -> KvLine<'_, T, U> {
+        KvLine {
+            buf: self,
+            first: true,
+        }
+    }
+}
+
+impl<T: Scratch, U: embedded_io::ErrorType> KvLine<'_, T, U> {
+    // Append the pair, rolling back on error
+    fn push(&mut self, key: &str, value: &impl Display) -> Result<(), Error<U::Error>> {
+        let start = self.buf.pos;
+        let ret = self.push_unchecked(key, value);
+        if ret.is_err() {
+            self.buf.pos = start;
+        }
+        ret
+    }
+
+    fn push_unchecked(&mut self, key: &str, value: &impl Display) -> Result<(), Error<U::Error>> {
+        if !self.first {
+            self.buf.write_all(b" ")?;
+        }
+        self.buf.write_all(key.as_bytes())?;
+        self.buf.write_all(b"=")?;
+        let start = self.buf.pos;
+        write!(self.buf, "{value}")?;
+        let end = self.buf.pos;
+        let value = &self.buf.buf.as_ref()[start..end];
+        if !value.is_empty()
+            && !value
+                .iter()
+                .any(|&b| matches!(b, b' ' | b'"' | b'=') || b.is_ascii_control())
+        {
+            return Ok(());
+        }
+        let len = value.iter().map(|&b| escape(b).1).sum::<usize>() + 2;
+        self.buf.buf.grow(start + len);
+        if self.buf.buf.as_ref().len() < start + len {
+            return Err(Error::Sync(embedded_io::SliceWriteError::Full));
+        }
+        // Quote and escape in place, back to front
+        let buf = self.buf.buf.as_mut();
+        let mut dst = start + len - 1;
+        buf[dst] = b'"';
+        for src in (start..end).rev() {
+            let (esc, n) = escape(buf[src]);
+            dst -= n;
+            buf[dst..dst + n].copy_from_slice(&esc[..n]);
+        }
+        buf[start] = b'"';
+        self.buf.pos = start + len;
+        Ok(())
+    }
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> KvLine<'_, T, U> {
+    /// Append a `key=value` pair.
+    ///
+    /// If the pair does not fit, what is buffered before it is flushed first.
+    pub async fn kv(
+        &mut self,
+        key: &str,
+        value: impl Display,
+    ) -> Result<&mut Self, Error<U::Error>> {
+        match self.push(key, &value) {
+            Err(Error::Sync(_)) if !self.buf.is_empty() => {
+                self.buf.force_flush().await?;
+                self.push(key, &value)?;
+            }
+            ret => ret?,
+        }
+        self.first = false;
+        Ok(self)
+    }
+
+    /// Terminate the line with `\n` and flush.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.kv_line().kv("a", 1).await?.end().await?;
+    /// assert_eq!(buf.sink_ref(), b"a=1\r\n");
+    /// # Ok::<(), awrite::Error<core::convert::Infallible>>(())
+    /// # }).unwrap()
+    /// ```
+    pub async fn end(&mut self) -> Result<(), Error<U::Error>> {
+        if self.buf.needs_room(b"\n") {
+            self.buf.force_flush().await?;
+        }
+        self.buf.write_all(b"\n")?;
+        embedded_io_async::Write::flush(self.buf).await
+    }
+}
@@ -2,21 +2,25 @@
 //! # tokio_test::block_on(async {
 //! use embedded_io::Write;
 //! use embedded_io_async::Write as Awrite;
-//! use awrite::{awrite, awriteln, AwriteBuf};
+//! use awrite::{aflush, awrite, awriteln, AwriteBuf};
 //!
 //! let mut async_sink = Vec::<u8>::new();
 //! let mut buf = AwriteBuf::new([0u8; 32], &mut async_sink);
 //!
-//! awrite!(buf, "Hello").unwrap();
-//! awriteln!(buf, "{:?} {}", 7, "bar").unwrap();
-//! awriteln!(&mut buf).unwrap();
+//! assert_eq!(awrite!(buf, "Hello"), Ok(5));
+//! assert_eq!(awriteln!(buf, "{:?} {}", 7, "bar"), Ok(6));
+//! assert_eq!(awriteln!(&mut buf), Ok(1));
+//!
+//! write!(buf, "batched").unwrap();
+//! write!(buf, " ").unwrap();
+//! aflush!(buf).unwrap();
 //!
 //! assert_eq!(awriteln!(buf, "{:032}", 0),
 //!     Err(embedded_io::WriteFmtError::Other(
 //!         awrite::Error::Sync(embedded_io::SliceWriteError::Full))
 //! ));
 //!
-//! assert_eq!(core::str::from_utf8(&async_sink).unwrap(), "Hello7 bar\n\n");
+//! assert_eq!(core::str::from_utf8(&async_sink).unwrap(), "Hello7 bar\n\nbatched ");
 //!
 //! let mut async_sink = [0u8; 8];
 //! let mut slic = &mut async_sink[..];
@@ -34,108 +38,3932 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(async_fn_in_trait)]
 use core::fmt::Debug;
+use core::future::Future;
 use embedded_io::ErrorType;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod scratch;
+#[cfg(feature = "alloc")]
+pub use scratch::BoundedVecScratch;
+pub use scratch::{Scratch, Uninit};
+mod array;
+pub use array::ArrayAwriteBuf;
+mod bits;
+pub use bits::BitWriter;
+mod buffered;
+pub use buffered::Buffered;
+mod builder;
+pub use builder::AwriteBufBuilder;
+mod double;
+pub use double::{DoubleBuf, Front};
+mod guard;
+pub use guard::WriteGuard;
+mod json;
+pub use json::{JsonObject, JsonValue};
+mod kv;
+pub use kv::KvLine;
+#[cfg(feature = "log")]
+mod logger;
+#[cfg(feature = "log")]
+pub use logger::LogBackend;
+mod passthrough;
+pub use passthrough::Passthrough;
+mod poll;
+pub use poll::PollWrite;
+mod pump;
+pub use pump::PumpError;
+pub mod sink;
+mod split;
+pub use split::{Flusher, Writer};
+#[cfg(feature = "embedded-hal-async")]
+mod timeout;
+#[cfg(feature = "embedded-hal-async")]
+pub use timeout::FlushTimeout;
+mod writer;
+pub use writer::{AwriteBufW, WriterError};
+
+/// Behavior when a write does not fit into the scratch
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Overflow {
+    /// Fail with `Error::Sync(SliceWriteError::Full)`
+    #[default]
+    Error,
+    /// Silently drop what does not fit.
+    ///
+    /// Writes are short while there is room and then pretend to succeed: data is lost.
+    Truncate,
+}
+
+/// Alignment of a value within a field, see [`AwriteBuf::write_padded`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Align {
+    /// Pad after the value
+    #[default]
+    Left,
+    /// Pad before the value
+    Right,
+}
+
+// `ErrorKind` is neither `Hash` nor `PartialOrd`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LatchedKind(embedded_io::ErrorKind);
+
+impl core::hash::Hash for LatchedKind {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(&self.0).hash(state);
+    }
+}
+
+impl PartialOrd for LatchedKind {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        (self == other).then_some(core::cmp::Ordering::Equal)
+    }
+}
+
+// Compared by address, like the pointer it is
+#[derive(Debug, Clone, Copy)]
+struct OnFlush(fn(usize));
+
+impl PartialEq for OnFlush {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::fn_addr_eq(self.0, other.0)
+    }
+}
+
+impl Eq for OnFlush {}
+
+impl core::hash::Hash for OnFlush {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (self.0 as usize).hash(state);
+    }
+}
+
+impl PartialOrd for OnFlush {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        (self.0 as usize).partial_cmp(&(other.0 as usize))
+    }
+}
+
 /// ```
 /// use awrite::AwriteBuf;
 ///
 /// // Async target (`embedded_io_async::Write`)
 /// let mut target = Vec::<u8>::new();
 ///
-/// // Borrowed scratch (`AsRef<[u8]> + `AsMut<[u8]>`)
+/// // Borrowed scratch (`Scratch`)
 /// let mut scratch = [0u8; 32];
 /// let _ = AwriteBuf::new(&mut scratch[..], &mut target);
 ///
 /// // Owned scratch
 /// let _ = AwriteBuf::new(scratch, target);
 /// ```
-#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+///
+/// `Default` is empty (nothing buffered) like `new()` with default scratch and sink.
+///
+/// Comparison and hashing cover the whole scratch, the position and the configuration.
+///
+/// ```
+/// use std::collections::HashSet;
+/// use awrite::AwriteBuf;
+///
+/// let mut set = HashSet::new();
+/// set.insert(AwriteBuf::new([0u8; 4], Vec::<u8>::new()));
+/// set.insert(AwriteBuf::new([0u8; 4], Vec::<u8>::new()));
+/// assert_eq!(set.len(), 1);
+/// ```
+///
+/// `AwriteBuf` is `Send`/`Sync` if `T` and `U` are. The futures of the async methods
+/// and of [`awrite!`] are `Send` if `T` and `U` are `Send` and the sink's futures are.
+///
+/// ```
+/// # use awrite::{awrite, AwriteBuf};
+/// fn assert_send<T: Send>(_: &T) {}
+/// fn assert_sync<T: Sync>(_: &T) {}
+///
+/// let mut scratch = [0u8; 32];
+/// let mut buf = AwriteBuf::new(&mut scratch[..], Vec::<u8>::new());
+/// assert_send(&buf);
+/// assert_sync(&buf);
+/// assert_send(&AwriteBuf::new([0u8; 32], &mut Vec::<u8>::new()));
+///
+/// let fut = async move {
+///     awrite!(buf, "{}", 42).unwrap();
+///     embedded_io_async::Write::flush(&mut buf).await.unwrap();
+/// };
+/// assert_send(&fut);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub struct AwriteBuf<T, U> {
-    // Could also go for embedded_io::Write + AsRef<[u8]> + Seek instead of pos...
+    // See AwriteBufW for a scratch that is an embedded_io::Write + Seek instead of pos.
     buf: T,
     sink: U,
     pos: usize,
+    total: u64,
+    // Bumped whenever buffered bytes are discarded, invalidates header handles
+    generation: u32,
+    since: Option<u64>,
+    depth: usize,
+    latched: Option<LatchedKind>,
+    config: Config,
+}
+
+// The settings, moved as a whole when the scratch or the sink change
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+struct Config {
+    flush_on: Option<u8>,
+    overflow: Overflow,
+    crlf: bool,
+    // Inverted so that `Default` matches `new()`
+    skip_sink_flush: bool,
+    retries: usize,
+    atomic: bool,
+    line_prefix: &'static [u8],
+    min_flush: usize,
+    // High-water mark in percent of the capacity, 0 is disabled
+    high_water: u8,
+    indent_unit: usize,
+    sticky: bool,
+    on_flush: Option<OnFlush>,
+    utf8: bool,
+}
+
+impl Config {
+    // `Default` is not `const`
+    const DEFAULT: Self = Self {
+        flush_on: None,
+        overflow: Overflow::Error,
+        crlf: false,
+        skip_sink_flush: false,
+        retries: 0,
+        atomic: false,
+        line_prefix: &[],
+        min_flush: 0,
+        high_water: 0,
+        indent_unit: 0,
+        sticky: false,
+        on_flush: None,
+        utf8: false,
+    };
 }
 
 impl<T, U> AwriteBuf<T, U> {
-    pub fn new(buf: T, sink: U) -> Self {
-        Self { buf, sink, pos: 0 }
+    pub const fn new(buf: T, sink: U) -> Self {
+        Self {
+            buf,
+            sink,
+            pos: 0,
+            total: 0,
+            generation: 0,
+            since: None,
+            depth: 0,
+            latched: None,
+            config: Config::DEFAULT,
+        }
+    }
+
+    /// Same as [`Self::new`], spelled out for `const` and `static` initializers.
+    ///
+    /// ```
+    /// use std::sync::Mutex;
+    /// use embedded_io::Write;
+    /// use awrite::{sink::DiscardSink, AwriteBuf};
+    ///
+    /// const EMPTY: AwriteBuf<[u8; 32], DiscardSink> = AwriteBuf::new_const([0; 32], DiscardSink);
+    /// static LOG: Mutex<AwriteBuf<[u8; 32], DiscardSink>> = Mutex::new(EMPTY);
+    ///
+    /// write!(LOG.lock().unwrap(), "boot").unwrap();
+    /// assert_eq!(LOG.lock().unwrap().written(), b"boot");
+    /// ```
+    ///
+    /// Borrowed parts work the same, e.g. a `&'static mut [u8]` scratch taken from
+    /// a `static_cell::StaticCell`.
+    pub const fn new_const(buf: T, sink: U) -> Self {
+        Self::new(buf, sink)
+    }
+
+    /// Make [`awrite!`]/[`awriteln!`] all-or-nothing.
+    ///
+    /// If formatting fails, e.g. because the scratch is full, the buffer is
+    /// rolled back to where it was before so no partial record is flushed later.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{awriteln, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.set_atomic(true);
+    /// write!(buf, "ab").unwrap();
+    /// assert!(awriteln!(buf, "{}", "too long").is_err());
+    /// assert_eq!(buf.written(), b"ab");
+    /// # })
+    /// ```
+    pub fn set_atomic(&mut self, atomic: bool) {
+        self.config.atomic = atomic;
+    }
+
+    /// Also flush the sink after draining the scratch into it (the default).
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// #[derive(Default)]
+    /// struct Flushes(usize);
+    ///
+    /// impl embedded_io::ErrorType for Flushes {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Flushes {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         Ok(buf.len())
+    ///     }
+    ///
+    ///     async fn flush(&mut self) -> Result<(), Self::Error> {
+    ///         self.0 += 1;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Flushes::default());
+    /// awrite!(buf, "a").unwrap();
+    /// assert_eq!(buf.sink_ref().0, 1);
+    ///
+    /// buf.set_flush_sink(false);
+    /// awrite!(buf, "b").unwrap();
+    /// assert_eq!(buf.sink_ref().0, 1);
+    /// # })
+    /// ```
+    ///
+    /// This makes nested buffers compose: flushing the outer buffer flushes the
+    /// inner buffer all the way to its sink.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// let inner = AwriteBuf::new([0u8; 64], Vec::<u8>::new());
+    /// let mut outer = AwriteBuf::new([0u8; 16], inner);
+    /// awrite!(outer, "nested").unwrap();
+    /// assert!(outer.sink_ref().is_empty());
+    /// assert_eq!(outer.into_sink().into_sink(), b"nested");
+    /// # })
+    /// ```
+    pub fn set_flush_sink(&mut self, flush_sink: bool) {
+        self.config.skip_sink_flush = !flush_sink;
+    }
+
+    /// Expand every `\n` to `\r\n` when writing into the scratch.
+    ///
+    /// The expansion is atomic: if both bytes don't fit, the write is short or fails
+    /// according to the [`Overflow`] policy.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{awriteln, AwriteBuf};
+    ///
+    /// let mut sink = Vec::<u8>::new();
+    /// let mut buf = AwriteBuf::new([0u8; 4], &mut sink);
+    /// buf.set_crlf(true);
+    /// assert_eq!(awriteln!(buf, "hi"), Ok(4));
+    /// assert!(awriteln!(buf, "hey").is_err());
+    /// drop(buf);
+    ///
+    /// assert_eq!(sink, b"hi\r\n");
+    /// # })
+    /// ```
+    pub fn set_crlf(&mut self, crlf: bool) {
+        self.config.crlf = crlf;
+    }
+
+    /// Select the [`Overflow`] policy.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{awrite, AwriteBuf, Overflow};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// assert!(awrite!(buf, "{}", 123456).is_err());
+    ///
+    /// let mut buf = buf.with_overflow(Overflow::Truncate);
+    /// buf.clear();
+    /// assert_eq!(awrite!(buf, "{}", 123456), Ok(4));
+    /// assert_eq!(buf.into_sink(), b"1234");
+    /// # })
+    /// ```
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.config.overflow = overflow;
+        self
+    }
+
+    /// Retry sink writes failing with `ErrorKind::Interrupted` up to `max` times per flush.
+    ///
+    /// Retries resume with the bytes the sink has not accepted yet.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::ErrorKind;
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// #[derive(Default)]
+    /// struct Flaky(usize, Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Flaky {
+    ///     type Error = ErrorKind;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Flaky {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         if self.0 < 2 {
+    ///             self.0 += 1;
+    ///             return Err(ErrorKind::Interrupted);
+    ///         }
+    ///         self.1.extend_from_slice(buf);
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Flaky::default()).with_retry(2);
+    /// awrite!(buf, "ok").unwrap();
+    /// assert_eq!(buf.sink_ref().1, b"ok");
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Flaky::default()).with_retry(1);
+    /// assert!(awrite!(buf, "fail").is_err());
+    /// # })
+    /// ```
+    pub fn with_retry(mut self, max: usize) -> Self {
+        self.config.retries = max;
+        self
+    }
+
+    /// Flush complete lines on every async `write`, see [`Self::set_line_buffered`].
+    pub fn new_line_buffered(buf: T, sink: U) -> Self {
+        let mut buf = Self::new(buf, sink);
+        buf.set_line_buffered(true);
+        buf
+    }
+
+    /// When enabled, the async `embedded_io_async::Write::write` flushes the scratch
+    /// up to and including the last `\n`. A trailing partial line stays buffered.
+    ///
+    /// The sync `embedded_io::Write` (and thus `write!()`) is unaffected.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io_async::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut sink = Vec::<u8>::new();
+    /// let mut buf = AwriteBuf::new_line_buffered([0u8; 16], &mut sink);
+    ///
+    /// buf.write_all(b"one\ntwo\nthr").await.unwrap();
+    /// assert_eq!(buf.len(), 3);
+    /// buf.write_all(b"ee").await.unwrap();
+    /// assert_eq!(buf.len(), 5);
+    /// buf.flush().await.unwrap();
+    ///
+    /// assert_eq!(sink, b"one\ntwo\nthree");
+    /// # })
+    /// ```
+    pub fn set_line_buffered(&mut self, line_buffered: bool) {
+        self.set_flush_on(line_buffered.then_some(b'\n'));
+    }
+
+    /// Like [`Self::set_line_buffered`] but flush up to and including the last `byte`.
+    ///
+    /// `Some(b'\n')` is line buffering, `None` (the default) disables auto-flushing.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io_async::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.set_flush_on(Some(0));
+    /// buf.write_all(b"rec1\0rec2\0re").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"rec1\0rec2\0");
+    /// assert_eq!(buf.written(), b"re");
+    /// # })
+    /// ```
+    pub fn set_flush_on(&mut self, byte: Option<u8>) {
+        self.config.flush_on = byte;
+    }
+
+    /// Make an explicit `flush()` with fewer than `min` bytes buffered a no-op.
+    ///
+    /// Batches small writes for sinks with a high per-transaction cost.
+    /// Flushes that make room in the scratch, [`Self::force_flush`] and teardown
+    /// like [`Self::flush_and_into_sink`] ignore the threshold. 0 (the default)
+    /// disables it.
+    pub fn set_min_flush(&mut self, min: usize) {
+        self.config.min_flush = min;
+    }
+
+    /// Make [`Self::needs_flush`] report once `percent` of the capacity is buffered.
+    ///
+    /// Flushing early leaves headroom for the next sync write. `percent` is
+    /// clamped to 100. 0 (the default) only reports a full scratch.
+    /// See [`crate::Buffered`] to flush at the mark automatically.
+    pub fn set_high_water(&mut self, percent: u8) {
+        self.config.high_water = percent.min(100);
+    }
+
+    /// Indent continuation lines with `prefix` when flushing to the sink.
+    ///
+    /// Every line of a flush but the first is preceded by `prefix`. Empty lines
+    /// are not indented. The prefix is written to the sink only, it does not take
+    /// room in the scratch, and it is not counted in [`Self::total_flushed`].
+    /// An empty prefix (the default) disables indentation.
+    /// Applies to the async `flush()` and everything built on it.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// buf.set_line_prefix(b"  | ");
+    /// awrite!(buf, "record\nfirst\n\nsecond\n").unwrap();
+    /// awrite!(buf, "next").unwrap();
+    /// assert_eq!(buf.sink_ref(), b"record\n  | first\n\n  | second\nnext");
+    /// # })
+    /// ```
+    pub fn set_line_prefix(&mut self, prefix: &'static [u8]) {
+        self.config.line_prefix = prefix;
+    }
+
+    /// Indent by `unit` spaces per [`Self::push_indent`] level when flushing.
+    ///
+    /// Every non-empty line of a flush, including the first, is indented by the
+    /// depth current at flush time, after any [`Self::set_line_prefix`] prefix.
+    /// Like the prefix, the indentation goes to the sink only and is not counted in
+    /// [`Self::total_flushed`]. 0 (the default) disables indentation.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{awriteln, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// buf.set_indent_unit(2);
+    /// awriteln!(buf, "soc").unwrap();
+    /// buf.push_indent();
+    /// awriteln!(buf, "uart0\nspi0").unwrap();
+    /// buf.push_indent();
+    /// awriteln!(buf, "flash").unwrap();
+    /// buf.pop_indent();
+    /// awriteln!(buf, "i2c0").unwrap();
+    /// buf.pop_indent();
+    /// buf.pop_indent();
+    /// awriteln!(buf, "end").unwrap();
+    /// assert_eq!(
+    ///     buf.sink_ref(),
+    ///     b"soc\n  uart0\n  spi0\n    flash\n  i2c0\nend\n"
+    /// );
+    /// # })
+    /// ```
+    pub fn set_indent_unit(&mut self, unit: usize) {
+        self.config.indent_unit = unit;
+    }
+
+    /// Indent the following flushes one level deeper.
+    pub fn push_indent(&mut self) {
+        self.depth += 1;
+    }
+
+    /// Indent the following flushes one level less, saturating at 0.
+    pub fn pop_indent(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Current indentation level
+    pub fn indent_depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Latch the first sink error and fail fast afterwards.
+    ///
+    /// After an `Error::Async`, writes and flushes fail with `Error::Latched`
+    /// of the same kind without touching the sink until [`Self::clear_error`].
+    /// Off by default. [`Self::replace_sink`] clears the latch.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::{ErrorKind, Write};
+    /// use awrite::{aflush, AwriteBuf, Error};
+    ///
+    /// let mut sink = [0u8; 4];
+    /// let mut buf = AwriteBuf::new([0u8; 16], &mut sink[..]);
+    /// buf.set_error_sticky(true);
+    /// write!(buf, "too long").unwrap();
+    /// assert!(matches!(aflush!(buf), Err(Error::Async(_))));
+    /// assert_eq!(buf.latched_error(), Some(ErrorKind::WriteZero));
+    /// assert_eq!(aflush!(buf), Err(Error::Latched(ErrorKind::WriteZero)));
+    /// assert!(buf.write(b"more").is_err());
+    /// assert_eq!(buf.written(), b"long");
+    ///
+    /// buf.clear_error();
+    /// buf.clear();
+    /// assert!(aflush!(buf).is_ok());
+    /// # })
+    /// ```
+    pub fn set_error_sticky(&mut self, sticky: bool) {
+        self.config.sticky = sticky;
+    }
+
+    /// Kind of the latched sink error, see [`Self::set_error_sticky`]
+    pub fn latched_error(&self) -> Option<embedded_io::ErrorKind> {
+        self.latched.map(|kind| kind.0)
+    }
+
+    /// Resume using the sink after a latched error.
+    pub fn clear_error(&mut self) {
+        self.latched = None;
+    }
+
+    /// Call `cb` with the number of bytes after every successful flush of buffered bytes.
+    ///
+    /// Applies to the async flushes that drain the scratch, not to failed or
+    /// empty ones. A function pointer keeps the buffer `Clone` and comparable,
+    /// keep state in a `static`. `None` (the default) removes the hook.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::sync::atomic::{AtomicUsize, Ordering};
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// static FLUSHED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.set_on_flush(Some(|n| {
+    ///     FLUSHED.fetch_add(n, Ordering::Relaxed);
+    /// }));
+    /// awrite!(buf, "{}", 1234).unwrap();
+    /// awrite!(buf, "").unwrap();
+    /// awrite!(buf, "ab").unwrap();
+    /// assert_eq!(FLUSHED.load(Ordering::Relaxed), 6);
+    ///
+    /// // Line-buffered writes and partial flushes count as well
+    /// buf.set_flush_on(Some(b'\n'));
+    /// embedded_io_async::Write::write_all(&mut buf, b"line\nrest").await.unwrap();
+    /// assert_eq!(FLUSHED.load(Ordering::Relaxed), 11);
+    /// buf.flush_upto(2).await.unwrap();
+    /// assert_eq!(FLUSHED.load(Ordering::Relaxed), 13);
+    /// # })
+    /// ```
+    pub fn set_on_flush(&mut self, cb: Option<fn(usize)>) {
+        self.config.on_flush = cb.map(OnFlush);
+    }
+
+    /// Hold back an incomplete trailing UTF-8 sequence when flushing.
+    ///
+    /// For UTF-8 data, so that no sink write ends within a multi-byte character.
+    /// The held back bytes go out with the next flush once the character is
+    /// complete. Applies to the async `flush()` and the flushes that make room.
+    /// Teardown like [`Self::flush_and_into_sink`] flushes everything.
+    /// A scratch that is full of an incomplete sequence is flushed as it is.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// // Count the separate writes the sink sees
+    /// #[derive(Default)]
+    /// struct Chunks(Vec<Vec<u8>>);
+    ///
+    /// impl embedded_io::ErrorType for Chunks {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Chunks {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         self.0.push(buf.to_vec());
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Chunks::default());
+    /// buf.set_utf8_boundaries(true);
+    /// buf.write_chunked(b"ab").await.unwrap();
+    /// // The scratch fills up in the middle of the three byte '€'
+    /// buf.write_chunked("€cd".as_bytes()).await.unwrap();
+    /// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+    /// let chunks = &buf.sink_ref().0;
+    /// assert_eq!(chunks.len(), 3);
+    /// for chunk in chunks {
+    ///     assert!(core::str::from_utf8(chunk).is_ok());
+    /// }
+    /// assert_eq!(chunks.concat(), "ab€cd".as_bytes());
+    /// # })
+    /// ```
+    pub fn set_utf8_boundaries(&mut self, utf8: bool) {
+        self.config.utf8 = utf8;
+    }
+
+    /// Bytes successfully flushed to the sink over the lifetime of the buffer
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// awrite!(buf, "ab").unwrap();
+    /// awrite!(buf, "cde").unwrap();
+    /// write!(buf, "dropped").unwrap();
+    /// buf.clear();
+    /// awrite!(buf, "f").unwrap();
+    /// assert_eq!(buf.total_flushed(), 6);
+    /// # })
+    /// ```
+    pub fn total_flushed(&self) -> u64 {
+        self.total
     }
 
     pub fn into_sink(self) -> U {
-        self.sink
+        self.into_parts().1
+    }
+
+    /// Take the sink if nothing is buffered.
+    ///
+    /// Otherwise hand back the buffer and the number of pending bytes.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// write!(buf, "late").unwrap();
+    /// let (mut buf, pending) = buf.try_into_sink().unwrap_err();
+    /// assert_eq!(pending, 4);
+    ///
+    /// aflush!(buf).unwrap();
+    /// assert_eq!(buf.try_into_sink().unwrap(), b"late");
+    /// # })
+    /// ```
+    pub fn try_into_sink(self) -> Result<U, (Self, usize)> {
+        if self.pos == 0 {
+            Ok(self.sink)
+        } else {
+            let pos = self.pos;
+            Err((self, pos))
+        }
+    }
+
+    /// Take apart into scratch and sink. Buffered bytes are dropped.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "x").unwrap();
+    /// let (scratch, sink) = buf.into_parts();
+    /// let buf = AwriteBuf::new(scratch, sink);
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn into_parts(self) -> (T, U) {
+        (self.buf, self.sink)
+    }
+
+    /// Swap in a new scratch and return the old one.
+    ///
+    /// Fails, handing back `new`, if bytes are still buffered: flush first.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let (mut small, mut large) = ([0u8; 8], [0u8; 64]);
+    /// let mut buf = AwriteBuf::new(&mut small[..], Vec::<u8>::new());
+    /// assert_eq!(buf.capacity(), 8);
+    ///
+    /// write!(buf, "pending").unwrap();
+    /// let large = buf.set_scratch(&mut large[..]).unwrap_err();
+    /// buf.clear();
+    /// let small = buf.set_scratch(large).unwrap();
+    /// assert_eq!((buf.capacity(), small.len()), (64, 8));
+    /// ```
+    pub fn set_scratch(&mut self, new: T) -> Result<T, T> {
+        if self.pos != 0 {
+            return Err(new);
+        }
+        Ok(core::mem::replace(&mut self.buf, new))
+    }
+
+    /// Copy the scratch and position, not the sink.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "head ").unwrap();
+    /// let snap = buf.snapshot();
+    /// write!(buf, "tail").unwrap();
+    ///
+    /// let mut fresh = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// fresh.restore(snap.clone());
+    /// assert_eq!(fresh.written(), b"head ");
+    /// buf.restore(snap);
+    /// assert_eq!(buf.written(), b"head ");
+    /// ```
+    pub fn snapshot(&self) -> Snapshot<T>
+    where
+        T: Clone,
+    {
+        Snapshot {
+            buf: self.buf.clone(),
+            pos: self.pos,
+        }
+    }
+
+    /// Replace scratch and position with a [`Snapshot`], keeping the sink.
+    pub fn restore(&mut self, snap: Snapshot<T>) {
+        self.buf = snap.buf;
+        self.pos = snap.pos;
+        self.since = None;
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Replace the sink by `f(sink)`, keeping the scratch and buffered bytes.
+    ///
+    /// The configuration and a latched error carry over, `f` usually wraps the same sink.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, sink::HexWrite, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "A").unwrap();
+    /// let mut buf = buf.map_sink(|s| HexWrite::new(s, None));
+    /// assert_eq!(buf.written(), b"A");
+    /// aflush!(buf).unwrap();
+    /// assert_eq!(buf.into_sink().into_inner(), b"41");
+    /// # })
+    /// ```
+    pub fn map_sink<V>(self, f: impl FnOnce(U) -> V) -> AwriteBuf<T, V> {
+        AwriteBuf {
+            buf: self.buf,
+            sink: f(self.sink),
+            pos: self.pos,
+            total: self.total,
+            generation: self.generation,
+            since: self.since,
+            depth: self.depth,
+            latched: self.latched,
+            config: self.config,
+        }
+    }
+
+    pub fn sink_ref(&self) -> &U {
+        &self.sink
+    }
+
+    /// Mutable access to the sink.
+    ///
+    /// This does not flush: buffered bytes stay pending.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "pending").unwrap();
+    /// buf.sink_mut().extend_from_slice(b"direct");
+    ///
+    /// assert_eq!(buf.len(), 7);
+    /// assert_eq!(buf.sink_ref(), b"direct");
+    /// ```
+    pub fn sink_mut(&mut self) -> &mut U {
+        &mut self.sink
+    }
+
+    /// Discard everything written since the last flush.
+    ///
+    /// The sink is not touched.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut sink = Vec::<u8>::new();
+    /// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+    ///
+    /// write!(buf, "dropped").unwrap();
+    /// buf.clear();
+    /// write!(buf, "kept").unwrap();
+    /// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+    ///
+    /// assert_eq!(sink, b"kept");
+    /// # })
+    /// ```
+    pub fn clear(&mut self) {
+        self.pos = 0;
+        self.since = None;
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Record the time of the first buffered byte, if not done yet.
+    ///
+    /// Call this after buffering. The record is dropped when the buffer empties.
+    pub fn stamp(&mut self, clock: &impl sink::Clock) {
+        if self.pos > 0 && self.since.is_none() {
+            self.since = Some(clock.now_millis());
+        }
+    }
+
+    /// The oldest buffered byte, as recorded by [`Self::stamp`], is older than `max_ms`.
+    ///
+    /// ```
+    /// use core::cell::Cell;
+    /// use embedded_io::Write;
+    /// use awrite::{sink::Clock, AwriteBuf};
+    ///
+    /// struct Mock(Cell<u64>);
+    ///
+    /// impl Clock for Mock {
+    ///     fn now_millis(&self) -> u64 {
+    ///         self.0.get()
+    ///     }
+    /// }
+    ///
+    /// let clock = Mock(Cell::new(100));
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "a").unwrap();
+    /// buf.stamp(&clock);
+    /// clock.0.set(140);
+    /// write!(buf, "b").unwrap();
+    /// buf.stamp(&clock);
+    /// assert!(!buf.age_exceeds(50, &clock));
+    /// clock.0.set(151);
+    /// assert!(buf.age_exceeds(50, &clock));
+    ///
+    /// buf.clear();
+    /// assert!(!buf.age_exceeds(50, &clock));
+    /// ```
+    pub fn age_exceeds(&self, max_ms: u64, clock: &impl sink::Clock) -> bool {
+        self.since
+            .is_some_and(|t| clock.now_millis().saturating_sub(t) > max_ms)
+    }
+}
+
+// The `[u8; 0]` only anchors the impl so that `AwriteBuf::with_array::<N>()` resolves.
+impl<U> AwriteBuf<[u8; 0], U> {
+    /// Construct with a zeroed `[u8; N]` scratch.
+    ///
+    /// ```
+    /// use awrite::AwriteBuf;
+    ///
+    /// let buf = AwriteBuf::with_array::<64>(Vec::<u8>::new());
+    /// assert_eq!(buf.capacity(), 64);
+    /// ```
+    ///
+    /// An empty scratch is rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// let buf = awrite::AwriteBuf::with_array::<0>(Vec::<u8>::new());
+    /// ```
+    pub fn with_array<const N: usize>(sink: U) -> AwriteBuf<[u8; N], U> {
+        const { assert!(N > 0, "empty scratch") };
+        AwriteBuf::new([0; N], sink)
+    }
+}
+
+impl<T, U: embedded_io_async::Read> AwriteBuf<T, U> {
+    /// Read from a full-duplex sink, bypassing the scratch.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// // Echoes everything written
+    /// #[derive(Default)]
+    /// struct Duplex(Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Duplex {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Duplex {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         self.0.extend_from_slice(buf);
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// impl embedded_io_async::Read for Duplex {
+    ///     async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+    ///         let n = buf.len().min(self.0.len());
+    ///         buf[..n].copy_from_slice(&self.0[..n]);
+    ///         self.0.drain(..n);
+    ///         Ok(n)
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Duplex::default());
+    /// awrite!(buf, "ping").unwrap();
+    /// let mut rx = [0u8; 8];
+    /// let n = buf.read_sink(&mut rx).await.unwrap();
+    /// assert_eq!(&rx[..n], b"ping");
+    /// # })
+    /// ```
+    pub async fn read_sink(&mut self, buf: &mut [u8]) -> Result<usize, Error<U::Error>> {
+        self.sink.read(buf).await.map_err(Error::Async)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<U> AwriteBuf<alloc::vec::Vec<u8>, U> {
+    /// Construct with an empty `Vec` scratch, allocated on the first write.
+    ///
+    /// The capacity is 0 until then.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::from_sink(Vec::<u8>::new());
+    /// assert_eq!(buf.capacity(), 0);
+    ///
+    /// awrite!(buf, "error {}", 42).unwrap();
+    /// assert!(buf.capacity() >= 8);
+    /// assert_eq!(buf.sink_ref(), b"error 42");
+    /// # })
+    /// ```
+    pub fn from_sink(sink: U) -> Self {
+        Self::new(alloc::vec::Vec::new(), sink)
+    }
+}
+
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use awrite::AwriteBuf;
+///
+/// let mut sink = Vec::<u8>::new();
+/// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+/// assert!(buf.is_empty());
+/// assert_eq!(buf.capacity(), 16);
+///
+/// write!(buf, "{}", 123).unwrap();
+/// assert_eq!((buf.len(), buf.remaining()), (3, 13));
+///
+/// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+/// assert_eq!((buf.len(), buf.remaining()), (0, 16));
+/// # })
+/// ```
+impl<T: Scratch, U> AwriteBuf<T, U> {
+    /// Like [`AwriteBuf::new`] but reject a scratch that can't hold a single byte.
+    ///
+    /// ```
+    /// use awrite::{AwriteBuf, EmptyScratch};
+    ///
+    /// assert!(AwriteBuf::new_checked([0u8; 1], ()).is_ok());
+    /// assert!(AwriteBuf::new_checked(awrite::Uninit::<8>::default(), ()).is_ok());
+    /// assert_eq!(AwriteBuf::new_checked([0u8; 0], ()).err(), Some(EmptyScratch));
+    /// ```
+    pub fn new_checked(mut buf: T, sink: U) -> Result<Self, EmptyScratch> {
+        buf.grow(1);
+        if buf.as_ref().is_empty() {
+            Err(EmptyScratch)
+        } else {
+            Ok(Self::new(buf, sink))
+        }
+    }
+
+    /// Number of bytes buffered since the last flush
+    pub fn len(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos == 0
+    }
+
+    /// Total scratch size, see [`Scratch::capacity`]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Bytes that can still be buffered without growing the scratch
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.pos
+    }
+
+    /// No more bytes can be buffered without a flush
+    ///
+    /// A growable scratch is full only once it can't grow further, see
+    /// [`Scratch::max_capacity`].
+    pub fn is_full(&self) -> bool {
+        self.pos >= self.buf.max_capacity()
+    }
+
+    /// The buffer is full or above the mark set by [`Self::set_high_water`].
+    ///
+    /// Poll this after sync writes to flush before the scratch fills.
+    /// The mark is relative to [`Scratch::max_capacity`]: an unbounded `Vec`
+    /// scratch never needs a flush.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.set_high_water(75);
+    /// buf.write_all(b"0123456789a").unwrap();
+    /// assert!(!buf.needs_flush());
+    /// buf.write_all(b"b").unwrap();
+    /// assert!(buf.needs_flush());
+    /// ```
+    pub fn needs_flush(&self) -> bool {
+        let mark = self
+            .buf
+            .max_capacity()
+            .saturating_mul(self.config.high_water as _)
+            / 100;
+        self.is_full() || (self.config.high_water != 0 && self.pos >= mark)
+    }
+
+    /// Buffering another `len` bytes would not fit.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// write!(buf, "12345").unwrap();
+    /// assert!(!buf.would_overflow(3));
+    /// assert!(buf.would_overflow(usize::MAX));
+    ///
+    /// if buf.would_overflow(4) {
+    ///     aflush!(buf).unwrap();
+    /// }
+    /// write!(buf, "6789").unwrap();
+    /// assert_eq!(buf.sink_ref(), b"12345");
+    /// write!(buf, "0000").unwrap();
+    /// assert!(buf.is_full());
+    /// # })
+    /// ```
+    pub fn would_overflow(&self, len: usize) -> bool {
+        len > self.remaining()
+    }
+
+    /// Everything written since the last flush or clear
+    pub fn written(&self) -> &[u8] {
+        &self.buf.as_ref()[..self.pos]
+    }
+
+    /// [`Self::written`], mutable to patch bytes in place before flushing
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "??body").unwrap();
+    /// let len = buf.len() as u8;
+    /// buf.written_mut()[..2].copy_from_slice(&[0xaa, len]);
+    /// aflush!(buf).unwrap();
+    /// assert_eq!(buf.sink_ref(), b"\xaa\x06body");
+    /// # })
+    /// ```
+    pub fn written_mut(&mut self) -> &mut [u8] {
+        &mut self.buf.as_mut()[..self.pos]
+    }
+
+    /// [`Self::written`] as `&str`
+    ///
+    /// ```
+    /// use core::fmt::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], ());
+    /// write!(buf, "{:.2}", 1.0 / 3.0).unwrap();
+    /// assert_eq!(buf.written_str(), Ok("0.33"));
+    /// ```
+    pub fn written_str(&self) -> Result<&str, core::str::Utf8Error> {
+        core::str::from_utf8(self.written())
+    }
+
+    /// Iterate over the complete buffered lines, without their `\n`.
+    ///
+    /// A trailing partial line is not yielded.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// write!(buf, "one\ntwo\nthr").unwrap();
+    /// assert!(buf.lines().eq([&b"one"[..], b"two"]));
+    /// assert_eq!(buf.len(), 11);
+    /// ```
+    pub fn lines(&self) -> impl Iterator<Item = &[u8]> {
+        let written = self.written();
+        let end = written
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        written[..end]
+            .split_inclusive(|&b| b == b'\n')
+            .map(|line| &line[..line.len() - 1])
+    }
+
+    /// Like [`Self::clear`] but also zero the scratch so that stale content can't leak.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// write!(buf, "secret").unwrap();
+    /// buf.reset();
+    /// assert!(buf.is_empty());
+    /// assert_eq!(buf.into_parts().0, [0; 8]);
+    /// ```
+    pub fn reset(&mut self) {
+        self.buf.as_mut().fill(0);
+        self.clear();
+    }
+
+    /// Lend the whole scratch to `f`, e.g. for a DMA transfer.
+    ///
+    /// Buffered bytes are discarded: the buffer is empty afterwards.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// write!(buf, "fmt").unwrap();
+    /// let n = buf.with_scratch(|s| {
+    ///     s.fill(0xaa);
+    ///     s.len()
+    /// });
+    /// assert_eq!(n, 8);
+    /// assert!(buf.is_empty());
+    /// ```
+    pub fn with_scratch<R>(&mut self, f: impl FnOnce(&mut [u8]) -> R) -> R {
+        self.clear();
+        f(self.buf.as_mut())
+    }
+
+    /// Flush into `sink` instead of the stored sink.
+    ///
+    /// Does not count towards [`Self::total_flushed`]. On error everything stays buffered.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut debug = Vec::<u8>::new();
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "snapshot").unwrap();
+    /// buf.drain_to(&mut debug).await.unwrap();
+    ///
+    /// assert_eq!(debug, b"snapshot");
+    /// assert!(buf.is_empty());
+    /// assert!(buf.sink_ref().is_empty());
+    /// # })
+    /// ```
+    pub async fn drain_to<V: embedded_io_async::Write>(
+        &mut self,
+        sink: &mut V,
+    ) -> Result<(), Error<V::Error>> {
+        sink.write_all(&self.buf.as_ref()[..self.pos])
+            .await
+            .map_err(Error::Async)?;
+        self.clear();
+        Ok(())
+    }
+
+    /// Append the buffered text to `out` instead of flushing to the sink, e.g. in tests.
+    ///
+    /// Like [`Self::drain_to`], this does not count towards [`Self::total_flushed`].
+    /// Invalid UTF-8 fails with `Error::Fmt` and stays buffered. The sink is not involved.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::{sink::DiscardSink, AwriteBuf, Error};
+    ///
+    /// let mut out = String::new();
+    /// let mut buf = AwriteBuf::new([0u8; 16], DiscardSink);
+    /// write!(buf, "a={} ", 1).unwrap();
+    /// buf.flush_into_string(&mut out).unwrap();
+    /// write!(buf, "b={}", 2).unwrap();
+    /// buf.flush_into_string(&mut out).unwrap();
+    /// assert_eq!(out, "a=1 b=2");
+    ///
+    /// buf.write_all(b"\xff").unwrap();
+    /// assert_eq!(buf.flush_into_string(&mut out), Err(Error::Fmt));
+    /// assert_eq!(buf.len(), 1);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn flush_into_string(
+        &mut self,
+        out: &mut alloc::string::String,
+    ) -> Result<(), Error<core::convert::Infallible>> {
+        out.push_str(self.written_str().or(Err(Error::Fmt))?);
+        self.clear();
+        Ok(())
+    }
+
+    /// Current write position, same as [`Self::len`]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Move the write position, saturating at the capacity.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut sink = Vec::<u8>::new();
+    /// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+    ///
+    /// write!(buf, "busy |").unwrap();
+    /// buf.set_position(buf.position() - 1);
+    /// write!(buf, "/").unwrap();
+    /// buf.set_position(100);
+    /// assert_eq!(buf.position(), 16);
+    /// buf.set_position(6);
+    /// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+    ///
+    /// assert_eq!(sink, b"busy /");
+    /// # })
+    /// ```
+    pub fn set_position(&mut self, pos: usize) {
+        let pos = pos.min(self.capacity());
+        self.buf.grow(pos);
+        self.pos = pos.min(self.buf.as_ref().len());
+    }
+}
+
+/// Scratch contents of an [`AwriteBuf`], see [`AwriteBuf::snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot<T> {
+    buf: T,
+    pos: usize,
+}
+
+/// Header region reserved by [`AwriteBuf::reserve_header`]
+#[derive(Debug, PartialEq, Eq)]
+pub struct HeaderHandle {
+    // Stream position, stays valid across partial flushes
+    start: u64,
+    len: usize,
+    generation: u32,
+}
+
+impl HeaderHandle {
+    /// Size of the reserved region
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// The scratch passed to [`AwriteBuf::new_checked`] can't hold any bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyScratch;
+
+impl core::fmt::Display for EmptyScratch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "empty scratch")
     }
 }
 
+impl core::error::Error for EmptyScratch {}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Error<E> {
+    /// The scratch is full, flush and retry. Its kind is always `ErrorKind::WriteZero`.
     Sync(embedded_io::SliceWriteError),
     Async(E),
+    /// A `Display`/`Debug` implementation returned an error
+    Fmt,
+    /// An earlier sink error of this kind latched, see [`AwriteBuf::set_error_sticky`]
+    Latched(embedded_io::ErrorKind),
 }
 
 impl<E: embedded_io::Error> embedded_io::Error for Error<E> {
     fn kind(&self) -> embedded_io::ErrorKind {
         match self {
             Self::Async(e) => e.kind(),
-            Self::Sync(e) => e.kind(),
+            Self::Sync(_) => embedded_io::ErrorKind::WriteZero,
+            Self::Fmt => embedded_io::ErrorKind::Other,
+            Self::Latched(kind) => *kind,
         }
     }
 }
 
-impl<T, U: ErrorType> ErrorType for AwriteBuf<T, U> {
-    type Error = Error<U::Error>;
+impl<E> Error<E> {
+    /// The scratch is full, as opposed to a sink or formatting error.
+    ///
+    /// ```
+    /// use embedded_io::{Error as _, ErrorKind, SliceWriteError};
+    /// use awrite::Error;
+    ///
+    /// let e = Error::<SliceWriteError>::Sync(SliceWriteError::Full);
+    /// assert!(e.is_scratch_full());
+    /// assert_eq!(e.kind(), ErrorKind::WriteZero);
+    ///
+    /// let e = Error::Async(SliceWriteError::Full);
+    /// assert!(!e.is_scratch_full());
+    /// assert!(!Error::<()>::Fmt.is_scratch_full());
+    /// ```
+    pub fn is_scratch_full(&self) -> bool {
+        matches!(self, Self::Sync(_))
+    }
 }
 
-// Sync Write behavior like &mut [u8]
-impl<T: AsMut<[u8]>, U: ErrorType> embedded_io::Write for AwriteBuf<T, U> {
-    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
-        let mut sli = &mut self.buf.as_mut()[self.pos..];
-        let written = sli.write(buf).map_err(Error::Sync)?;
-        self.pos += written;
-        Ok(written)
+/// Type-erased sink error, see [`Error::boxed`]
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub struct DynError(pub alloc::boxed::Box<dyn embedded_io::Error>);
+
+#[cfg(feature = "alloc")]
+impl embedded_io::Error for DynError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        self.0.kind()
     }
+}
 
-    fn flush(&mut self) -> Result<(), Self::Error> {
-        Ok(())
+/// [`Error`] with the sink error erased, e.g. for `dyn` sinks
+#[cfg(feature = "alloc")]
+pub type BoxError = Error<DynError>;
+
+#[cfg(feature = "alloc")]
+impl<E: embedded_io::Error + 'static> Error<E> {
+    /// Erase the sink error type, keeping its `kind()`.
+    ///
+    /// ```
+    /// use embedded_io::{Error as _, ErrorKind};
+    /// use awrite::{BoxError, Error};
+    ///
+    /// #[derive(Debug)]
+    /// struct Timeout;
+    ///
+    /// impl embedded_io::Error for Timeout {
+    ///     fn kind(&self) -> ErrorKind {
+    ///         ErrorKind::TimedOut
+    ///     }
+    /// }
+    ///
+    /// let e: BoxError = Error::Async(Timeout).boxed();
+    /// assert_eq!(e.kind(), ErrorKind::TimedOut);
+    /// assert!(matches!(e, Error::Async(_)));
+    /// assert_eq!(Error::<Timeout>::Fmt.boxed().kind(), ErrorKind::Other);
+    /// ```
+    pub fn boxed(self) -> BoxError {
+        match self {
+            Self::Sync(e) => Error::Sync(e),
+            Self::Async(e) => Error::Async(DynError(alloc::boxed::Box::new(e))),
+            Self::Fmt => Error::Fmt,
+            Self::Latched(kind) => Error::Latched(kind),
+        }
     }
 }
 
-impl<T: AsRef<[u8]> + AsMut<[u8]>, U: embedded_io_async::Write> embedded_io_async::Write
-    for AwriteBuf<T, U>
-{
-    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
-        embedded_io::Write::write(self, buf)
+/// Flatten the macro result so `?` works in functions returning [`Error`].
+///
+/// ```
+/// use embedded_io::WriteFmtError;
+/// use awrite::Error;
+///
+/// let e: Error<()> = WriteFmtError::Other(Error::Async(())).into();
+/// assert_eq!(e, Error::Async(()));
+/// let e: Error<()> = WriteFmtError::FmtError.into();
+/// assert_eq!(e, Error::Fmt);
+/// ```
+impl<E> From<embedded_io::WriteFmtError<Error<E>>> for Error<E> {
+    fn from(e: embedded_io::WriteFmtError<Error<E>>) -> Self {
+        match e {
+            embedded_io::WriteFmtError::Other(e) => e,
+            embedded_io::WriteFmtError::FmtError => Self::Fmt,
+        }
     }
+}
 
-    async fn flush(&mut self) -> Result<(), Self::Error> {
-        self.sink
-            .write_all(&self.buf.as_ref()[..self.pos])
-            .await
-            .map_err(Error::Async)?;
-        self.pos = 0;
-        Ok(())
+/// ```
+/// use core::error::Error as _;
+/// use awrite::Error;
+///
+/// let e = Error::<std::io::Error>::Sync(embedded_io::SliceWriteError::Full);
+/// assert_eq!(e.to_string(), "sync slice write error");
+/// assert!(e.source().is_none());
+///
+/// let e = Error::Async(std::io::Error::other("gone"));
+/// assert_eq!(e.to_string(), "async sink error: gone");
+/// assert_eq!(e.source().unwrap().to_string(), "gone");
+/// ```
+impl<E: core::fmt::Display> core::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Sync(_) => write!(f, "sync slice write error"),
+            Self::Async(e) => write!(f, "async sink error: {e}"),
+            Self::Fmt => write!(f, "formatting error"),
+            Self::Latched(kind) => write!(f, "latched sink error: {kind:?}"),
+        }
     }
 }
 
-#[macro_export]
-macro_rules! awrite {
-    ($aw:expr, $($tt:tt)*) => {
-        match write!($aw, $($tt)*) {
-            Ok(_) => embedded_io_async::Write::flush(&mut $aw).await.map_err(Into::into),
-            e => e
+impl<E: core::error::Error + 'static> core::error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Sync(_) | Self::Fmt | Self::Latched(_) => None,
+            Self::Async(e) => Some(e),
         }
-    };
+    }
 }
 
-#[macro_export]
-macro_rules! awriteln {
-    ($aw:expr $(,)?) => {
-        awrite!($aw, "\n")
-    };
-    ($aw:expr, $($tt:tt)*) => {
-        match writeln!($aw, $($tt)*) {
-            Ok(_) => embedded_io_async::Write::flush(&mut $aw).await.map_err(Into::into),
-            e => e
+/// ```
+/// use std::io::ErrorKind;
+/// use awrite::Error;
+///
+/// let e: std::io::Error = Error::<std::io::Error>::Sync(embedded_io::SliceWriteError::Full).into();
+/// assert_eq!(e.kind(), ErrorKind::WriteZero);
+/// let e: std::io::Error = Error::Async(std::io::Error::from(ErrorKind::BrokenPipe)).into();
+/// assert_eq!(e.kind(), ErrorKind::BrokenPipe);
+/// ```
+#[cfg(feature = "std")]
+impl<E: Into<std::io::Error>> From<Error<E>> for std::io::Error {
+    fn from(e: Error<E>) -> Self {
+        match e {
+            Error::Sync(_) => std::io::ErrorKind::WriteZero.into(),
+            Error::Async(e) => e.into(),
+            Error::Fmt => std::io::ErrorKind::Other.into(),
+            Error::Latched(kind) => std::io::ErrorKind::from(kind).into(),
         }
-    };
+    }
+}
+
+impl<T, U: ErrorType> ErrorType for AwriteBuf<T, U> {
+    type Error = Error<U::Error>;
+}
+
+impl<T, U: ErrorType> AwriteBuf<T, U> {
+    fn check_latched(&self) -> Result<(), Error<U::Error>> {
+        self.latched
+            .map_or(Ok(()), |kind| Err(Error::Latched(kind.0)))
+    }
+
+    // Latch a sink error if sticky
+    fn latch<R>(&mut self, ret: Result<R, Error<U::Error>>) -> Result<R, Error<U::Error>> {
+        if let Err(Error::Async(e)) = &ret {
+            if self.config.sticky {
+                self.latched = Some(LatchedKind(embedded_io::Error::kind(e)));
+            }
+        }
+        ret
+    }
+}
+
+impl<T: Scratch, U> AwriteBuf<T, U> {
+    // Sync Write behavior like &mut [u8]
+    fn push(&mut self, buf: &[u8]) -> Result<usize, embedded_io::SliceWriteError> {
+        if !self.config.crlf {
+            return self.put(buf);
+        }
+        match buf.iter().position(|b| *b == b'\n') {
+            Some(0) => {
+                // Expand atomically and count it as the one input byte
+                self.buf.grow(self.pos + 2);
+                if self.remaining() >= 2 {
+                    self.put(b"\r\n")?;
+                    Ok(1)
+                } else if self.config.overflow == Overflow::Truncate {
+                    Ok(1)
+                } else {
+                    Err(embedded_io::SliceWriteError::Full)
+                }
+            }
+            Some(i) => self.put(&buf[..i]),
+            None => self.put(buf),
+        }
+    }
+
+    fn put(&mut self, buf: &[u8]) -> Result<usize, embedded_io::SliceWriteError> {
+        self.buf.grow(self.pos + buf.len());
+        let mut sli = &mut self.buf.as_mut()[self.pos..];
+        let written = match embedded_io::Write::write(&mut sli, buf) {
+            Err(embedded_io::SliceWriteError::Full)
+                if self.config.overflow == Overflow::Truncate =>
+            {
+                return Ok(buf.len());
+            }
+            ret => ret?,
+        };
+        self.pos += written;
+        Ok(written)
+    }
+
+    fn push_all(&mut self, mut buf: &[u8]) -> Result<(), embedded_io::SliceWriteError> {
+        while !buf.is_empty() {
+            let written = self.push(buf)?;
+            buf = &buf[written..];
+        }
+        Ok(())
+    }
+
+    // The next push of `buf` can't make progress: flush first
+    fn needs_room(&self, buf: &[u8]) -> bool {
+        let need = if self.config.crlf && buf.first() == Some(&b'\n') {
+            2
+        } else {
+            1
+        };
+        self.remaining() < need
+    }
+
+    // Bytes to flush, short of an incomplete UTF-8 sequence if enabled
+    fn flushable(&self) -> usize {
+        if !self.config.utf8 {
+            return self.pos;
+        }
+        let buf = &self.buf.as_ref()[..self.pos];
+        let Some(lead) = (self.pos.saturating_sub(4)..self.pos)
+            .rev()
+            .find(|&i| buf[i] & 0xc0 != 0x80)
+        else {
+            return self.pos;
+        };
+        let len = match buf[lead] {
+            0xf0.. => 4,
+            0xe0.. => 3,
+            0xc0.. => 2,
+            _ => 1,
+        };
+        if lead + len <= self.pos || (lead == 0 && self.is_full()) {
+            self.pos
+        } else {
+            lead
+        }
+    }
+
+    // Report `n` flushed bytes to the hook
+    fn flushed(&self, n: usize) {
+        if let Some(cb) = self.config.on_flush.filter(|_| n > 0) {
+            (cb.0)(n);
+        }
+    }
+
+    // Drop the first `sent` bytes the sink has accepted
+    fn consume(&mut self, sent: usize) {
+        self.total += sent as u64;
+        self.buf.as_mut().copy_within(sent..self.pos, 0);
+        self.pos -= sent;
+        if self.pos == 0 {
+            self.since = None;
+        }
+    }
+}
+
+impl<T: Scratch, U: ErrorType> embedded_io::Write for AwriteBuf<T, U> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.check_latched()?;
+        self.push(buf).map_err(Error::Sync)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Outcome of [`AwriteBuf::try_write`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WriteStatus {
+    /// Number of input bytes accepted into the scratch
+    pub written: usize,
+    /// The scratch is full and must be flushed to accept more
+    pub flush_needed: bool,
+}
+
+impl<T: Scratch, U: ErrorType> AwriteBuf<T, U> {
+    /// Format `value` into the emptied scratch and return it, without flushing.
+    ///
+    /// Anything buffered before is discarded. Output truncated within a UTF-8
+    /// sequence by [`Overflow::Truncate`] is reported as `Error::Fmt`.
+    ///
+    /// ```
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// assert_eq!(buf.display(-42), Ok("-42"));
+    /// assert_eq!(buf.display(7), Ok("7"));
+    /// assert!(buf.sink_ref().is_empty());
+    /// ```
+    pub fn display(&mut self, value: impl core::fmt::Display) -> Result<&str, Error<U::Error>> {
+        self.clear();
+        embedded_io::Write::write_fmt(self, format_args!("{value}"))?;
+        self.written_str().or(Err(Error::Fmt))
+    }
+
+    /// Buffer as much of `buf` as fits without touching the sink.
+    ///
+    /// Nothing is dropped, also not with [`Overflow::Truncate`]: what does not
+    /// fit is left to the caller.
+    ///
+    /// ```
+    /// use awrite::{AwriteBuf, Overflow, WriteStatus};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// assert_eq!(
+    ///     buf.try_write(b"ab"),
+    ///     Ok(WriteStatus { written: 2, flush_needed: false })
+    /// );
+    /// assert_eq!(
+    ///     buf.try_write(b"cdef"),
+    ///     Ok(WriteStatus { written: 2, flush_needed: true })
+    /// );
+    /// assert!(buf.sink_ref().is_empty());
+    ///
+    /// let mut buf = buf.with_overflow(Overflow::Truncate);
+    /// assert_eq!(
+    ///     buf.try_write(b"g"),
+    ///     Ok(WriteStatus { written: 0, flush_needed: true })
+    /// );
+    /// ```
+    pub fn try_write(&mut self, buf: &[u8]) -> Result<WriteStatus, Error<U::Error>> {
+        let mut written = 0;
+        while written < buf.len() {
+            // Stop before `push()` would truncate
+            self.buf.grow(self.pos + 2);
+            if self.needs_room(&buf[written..]) {
+                break;
+            }
+            match self.push(&buf[written..]) {
+                Ok(n) => written += n,
+                Err(embedded_io::SliceWriteError::Full) => break,
+                Err(e) => return Err(Error::Sync(e)),
+            }
+        }
+        Ok(WriteStatus {
+            written,
+            flush_needed: written < buf.len() || self.is_full(),
+        })
+    }
+
+    /// View as the blocking [`embedded_io::Write`] that buffers into the scratch.
+    ///
+    /// ```
+    /// // Both traits in scope, the view picks one
+    /// use embedded_io::Write as _;
+    /// use embedded_io_async::Write as _;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.as_sync().write_all(b"sync").unwrap();
+    /// assert_eq!(buf.written(), b"sync");
+    /// ```
+    pub fn as_sync(&mut self) -> &mut impl embedded_io::Write<Error = Error<U::Error>> {
+        self
+    }
+
+    /// Buffer as much of `buf` as fits, or refuse with `Poll::Pending` if the scratch is full.
+    ///
+    /// No waker is registered: flush or drop the data. See [`AwriteBuf::write_backpressured`].
+    ///
+    /// ```
+    /// use core::task::Poll;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// assert_eq!(buf.try_write_backpressured(b"abcdef"), Poll::Ready(Ok(4)));
+    /// assert_eq!(buf.try_write_backpressured(b"ef"), Poll::Pending);
+    /// ```
+    pub fn try_write_backpressured(
+        &mut self,
+        buf: &[u8],
+    ) -> core::task::Poll<Result<usize, Error<U::Error>>> {
+        if self.is_full() && !buf.is_empty() {
+            return core::task::Poll::Pending;
+        }
+        core::task::Poll::Ready(self.try_write(buf).map(|status| status.written))
+    }
+
+    /// Reserve `n` zeroed bytes for a header that is filled in later with
+    /// [`Self::commit_header`].
+    ///
+    /// Fails with `Error::Sync(SliceWriteError::Full)` if there is no room.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// let header = buf.reserve_header(4).unwrap();
+    /// write!(buf, "body").unwrap();
+    /// let len = (buf.len() - header.len()) as u32;
+    /// buf.commit_header(header, &len.to_be_bytes());
+    /// aflush!(buf).unwrap();
+    /// assert_eq!(buf.sink_ref(), b"\0\0\0\x04body");
+    /// assert!(buf.reserve_header(17).is_err());
+    /// # })
+    /// ```
+    pub fn reserve_header(&mut self, n: usize) -> Result<HeaderHandle, Error<U::Error>> {
+        self.buf.grow(self.pos + n);
+        if self.would_overflow(n) {
+            return Err(Error::Sync(embedded_io::SliceWriteError::Full));
+        }
+        let start = self.pos;
+        self.buf.as_mut()[start..start + n].fill(0);
+        self.pos += n;
+        Ok(HeaderHandle {
+            start: self.total + start as u64,
+            len: n,
+            generation: self.generation,
+        })
+    }
+
+    /// Fill the region reserved by [`Self::reserve_header`] with `bytes`.
+    ///
+    /// # Panics
+    ///
+    /// If `bytes` is not exactly as long as the reservation, or if the region
+    /// is no longer buffered because it was flushed, or discarded by
+    /// [`Self::clear`], [`Self::restore`] or anything else that clears the scratch.
+    ///
+    /// ```should_panic
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// let header = buf.reserve_header(2).unwrap();
+    /// write!(buf, "old").unwrap();
+    /// buf.drain_to(&mut Vec::<u8>::new()).await.unwrap();
+    /// write!(buf, "newdata").unwrap();
+    /// buf.commit_header(header, b"!!");
+    /// # })
+    /// ```
+    pub fn commit_header(&mut self, h: HeaderHandle, bytes: &[u8]) {
+        assert_eq!(bytes.len(), h.len, "header length mismatch");
+        assert_eq!(h.generation, self.generation, "header discarded");
+        let start = h
+            .start
+            .checked_sub(self.total)
+            .expect("header already flushed") as usize;
+        assert!(start + h.len <= self.pos, "header no longer buffered");
+        self.buf.as_mut()[start..start + h.len].copy_from_slice(bytes);
+    }
+
+    /// Append the bytes buffered in `other`, e.g. to assemble a record from
+    /// separately formatted fragments.
+    ///
+    /// The bytes are copied as they are, without CRLF translation. If they don't
+    /// all fit, nothing is appended and this fails with
+    /// `Error::Sync(SliceWriteError::Full)`. `other` is left untouched.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, AwriteBuf};
+    ///
+    /// let mut head = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// let mut body = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// write!(head, "id=3 ").unwrap();
+    /// write!(body, "v={}", 12).unwrap();
+    /// head.merge_from(&body).unwrap();
+    /// head.merge_from(&body).unwrap();
+    /// assert!(head.merge_from(&body).is_err());
+    /// aflush!(head).unwrap();
+    /// assert_eq!(head.sink_ref(), b"id=3 v=12v=12");
+    /// # })
+    /// ```
+    pub fn merge_from<T2: AsRef<[u8]>, V>(
+        &mut self,
+        other: &AwriteBuf<T2, V>,
+    ) -> Result<(), Error<U::Error>> {
+        let data = &other.buf.as_ref()[..other.pos];
+        self.buf.grow(self.pos + data.len());
+        if self.would_overflow(data.len()) {
+            return Err(Error::Sync(embedded_io::SliceWriteError::Full));
+        }
+        self.buf.as_mut()[self.pos..self.pos + data.len()].copy_from_slice(data);
+        self.pos += data.len();
+        Ok(())
+    }
+}
+
+/// Move the write position within the scratch, e.g. to back-patch a header.
+///
+/// The flush always covers the scratch up to the current position.
+/// `SeekFrom::End` is relative to the capacity. Positions before the start
+/// clamp to zero, positions beyond the capacity are an error.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::{Seek, SeekFrom, Write};
+/// use awrite::AwriteBuf;
+///
+/// let mut sink = Vec::<u8>::new();
+/// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+///
+/// buf.write_all(b"..").unwrap();
+/// write!(buf, "payload").unwrap();
+/// let end = buf.stream_position().unwrap();
+/// buf.rewind().unwrap();
+/// buf.write_all(&[0, end as u8 - 2]).unwrap();
+/// buf.seek(SeekFrom::Start(end)).unwrap();
+/// assert!(buf.seek(SeekFrom::End(1)).is_err());
+/// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+///
+/// assert_eq!(sink, b"\0\x07payload");
+/// # })
+/// ```
+impl<T: Scratch, U: ErrorType> embedded_io::Seek for AwriteBuf<T, U> {
+    fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, Self::Error> {
+        let offset =
+            |n: i64| isize::try_from(n).unwrap_or(if n < 0 { isize::MIN } else { isize::MAX });
+        let pos = match pos {
+            embedded_io::SeekFrom::Start(n) => usize::try_from(n).unwrap_or(usize::MAX),
+            embedded_io::SeekFrom::End(n) => self.capacity().saturating_add_signed(offset(n)),
+            embedded_io::SeekFrom::Current(n) => self.pos.saturating_add_signed(offset(n)),
+        };
+        if pos > self.capacity() {
+            return Err(Error::Sync(embedded_io::SliceWriteError::Full));
+        }
+        self.set_position(pos);
+        Ok(self.pos as u64)
+    }
+}
+
+impl<T: Scratch, U: ErrorType> embedded_io_async::Seek for AwriteBuf<T, U> {
+    async fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, Self::Error> {
+        embedded_io::Seek::seek(self, pos)
+    }
+}
+
+/// Ready when there is room in the scratch or when the sink is ready to take a flush.
+///
+/// ```
+/// use core::convert::Infallible;
+/// use embedded_io::{ErrorType, Write, WriteReady};
+/// use awrite::AwriteBuf;
+///
+/// struct Stalled;
+///
+/// impl ErrorType for Stalled {
+///     type Error = Infallible;
+/// }
+///
+/// impl WriteReady for Stalled {
+///     fn write_ready(&mut self) -> Result<bool, Infallible> {
+///         Ok(false)
+///     }
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 4], Stalled);
+/// assert_eq!(buf.write_ready(), Ok(true));
+/// buf.write_all(b"full").unwrap();
+/// assert_eq!(buf.write_ready(), Ok(false));
+/// ```
+impl<T: Scratch, U: embedded_io::WriteReady> embedded_io::WriteReady for AwriteBuf<T, U> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.remaining() > 0 || self.sink.write_ready().map_err(Error::Async)?)
+    }
+}
+
+/// Formatting without `embedded_io`. Fails with `core::fmt::Error` when the scratch is full.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use core::fmt::Write;
+/// use awrite::AwriteBuf;
+///
+/// let mut sink = Vec::<u8>::new();
+/// let mut buf = AwriteBuf::new([0u8; 8], &mut sink);
+///
+/// buf.write_str("fmt").unwrap();
+/// assert!(buf.write_str(" overflow").is_err());
+/// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+///
+/// assert_eq!(sink, b"fmt over");
+/// # })
+/// ```
+impl<T: Scratch, U> core::fmt::Write for AwriteBuf<T, U> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.push_all(s.as_bytes()).or(Err(core::fmt::Error))
+    }
+}
+
+/// Format into `scratch` and return the formatted prefix, without async or a sink.
+///
+/// Overflow and formatting errors are both reported as `SliceWriteError::Full`.
+/// The scratch content is unspecified after an error.
+///
+/// ```
+/// let mut scratch = [0u8; 8];
+/// assert_eq!(awrite::format_into(&mut scratch, format_args!("{:04}", 7)), Ok("0007"));
+/// assert!(awrite::format_into(&mut scratch, format_args!("{:09}", 7)).is_err());
+/// ```
+pub fn format_into<'a>(
+    scratch: &'a mut [u8],
+    args: core::fmt::Arguments<'_>,
+) -> Result<&'a str, embedded_io::SliceWriteError> {
+    let mut buf = AwriteBuf::new(scratch, ());
+    core::fmt::Write::write_fmt(&mut buf, args).or(Err(embedded_io::SliceWriteError::Full))?;
+    let len = buf.len();
+    let scratch: &'a [u8] = buf.into_parts().0;
+    Ok(core::str::from_utf8(&scratch[..len]).expect("formatted output is UTF-8"))
+}
+
+/// Format into exactly `N` bytes, e.g. for a fixed layout field.
+///
+/// Longer output fails with `Error::Sync(SliceWriteError::Full)`, shorter output
+/// with `Error::Fmt`.
+///
+/// ```
+/// use awrite::{write_fixed, Error};
+///
+/// assert_eq!(write_fixed::<4>(format_args!("{:04}", 7)), Ok(*b"0007"));
+/// assert_eq!(write_fixed::<4>(format_args!("{}", 7)), Err(Error::Fmt));
+/// assert_eq!(
+///     write_fixed::<4>(format_args!("{}", 12345)),
+///     Err(Error::Sync(embedded_io::SliceWriteError::Full))
+/// );
+/// ```
+pub fn write_fixed<const N: usize>(
+    args: core::fmt::Arguments<'_>,
+) -> Result<[u8; N], Error<core::convert::Infallible>> {
+    let mut out = [0; N];
+    let mut rest = &mut out[..];
+    embedded_io::Write::write_fmt(&mut rest, args).map_err(|e| match e {
+        embedded_io::WriteFmtError::Other(e) => Error::Sync(e),
+        _ => Error::Fmt,
+    })?;
+    if !rest.is_empty() {
+        return Err(Error::Fmt);
+    }
+    Ok(out)
+}
+
+/// Number of bytes `args` formats to, without storing them.
+///
+/// If a `Display`/`Debug` implementation fails, the bytes up to the failure are counted.
+///
+/// ```
+/// let pi = format_args!("{:.3}", 3.14159);
+/// assert_eq!(awrite::fmt_len(pi), 5);
+/// assert_eq!(awrite::fmt_len(format_args!("{}µs", 20)), "20µs".len());
+/// ```
+pub fn fmt_len(args: core::fmt::Arguments<'_>) -> usize {
+    struct Counter(usize);
+
+    impl core::fmt::Write for Counter {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    core::fmt::Write::write_fmt(&mut counter, args).ok();
+    counter.0
+}
+
+/// Encode defmt frames into the scratch, e.g. from a `defmt::Logger`.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::AwriteBuf;
+///
+/// let mut sink = Vec::<u8>::new();
+/// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+/// let mut encoder = defmt::Encoder::new();
+///
+/// buf.defmt_start_frame(&mut encoder).unwrap();
+/// buf.defmt_write(&mut encoder, &[1, 0, 2]).unwrap();
+/// buf.defmt_end_frame(&mut encoder).unwrap();
+/// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+///
+/// let mut expect = Vec::new();
+/// let mut encoder = defmt::Encoder::new();
+/// encoder.start_frame(|b| expect.extend_from_slice(b));
+/// encoder.write(&[1, 0, 2], |b| expect.extend_from_slice(b));
+/// encoder.end_frame(|b| expect.extend_from_slice(b));
+/// assert_eq!(sink, expect);
+///
+/// let mut buf = AwriteBuf::new([0u8; 2], Vec::<u8>::new());
+/// buf.defmt_start_frame(&mut encoder).unwrap();
+/// assert_eq!(
+///     buf.defmt_write(&mut encoder, &[1; 8]),
+///     Err(awrite::Error::Sync(embedded_io::SliceWriteError::Full))
+/// );
+/// # })
+/// ```
+#[cfg(feature = "defmt")]
+impl<T: Scratch, U: ErrorType> AwriteBuf<T, U> {
+    pub fn defmt_start_frame(
+        &mut self,
+        encoder: &mut defmt::Encoder,
+    ) -> Result<(), Error<U::Error>> {
+        self.defmt_encode(|write| encoder.start_frame(write))
+    }
+
+    pub fn defmt_write(
+        &mut self,
+        encoder: &mut defmt::Encoder,
+        bytes: &[u8],
+    ) -> Result<(), Error<U::Error>> {
+        self.defmt_encode(|write| encoder.write(bytes, write))
+    }
+
+    pub fn defmt_end_frame(&mut self, encoder: &mut defmt::Encoder) -> Result<(), Error<U::Error>> {
+        self.defmt_encode(|write| encoder.end_frame(write))
+    }
+
+    // The encoder callbacks can't fail: latch the first error
+    fn defmt_encode(
+        &mut self,
+        f: impl FnOnce(&mut dyn FnMut(&[u8])),
+    ) -> Result<(), Error<U::Error>> {
+        let mut ret = Ok(());
+        f(&mut |bytes| {
+            if ret.is_ok() {
+                ret = self.push_all(bytes);
+            }
+        });
+        ret.map_err(Error::Sync)
+    }
+}
+
+/// Format into the scratch with `ufmt`, see [`auwrite!`].
+#[cfg(feature = "ufmt")]
+impl<T: Scratch, U> ufmt::uWrite for AwriteBuf<T, U> {
+    type Error = embedded_io::SliceWriteError;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.push_all(s.as_bytes())
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<T: Scratch, U: embedded_io_async::Write> AwriteBuf<T, U> {
+    /// Run `f` to format into the scratch, then flush, yielding the number of bytes flushed.
+    ///
+    /// This is what [`auwrite!`] expands to. See [`AwriteBuf::awrite_fmt`].
+    pub async fn auwrite_with(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<(), embedded_io::SliceWriteError>,
+    ) -> Result<usize, Error<U::Error>> {
+        let start = self.pos;
+        if let Err(e) = f(self) {
+            if self.config.atomic {
+                self.pos = start;
+            }
+            return Err(Error::Sync(e));
+        }
+        let len = self.pos;
+        embedded_io_async::Write::flush(self).await?;
+        Ok(len - self.pos)
+    }
+}
+
+/// A failed flush keeps only the bytes the sink has not accepted.
+/// A later flush resumes with those.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::{ErrorKind, ErrorType, Write};
+/// use awrite::AwriteBuf;
+///
+/// struct Flaky(Vec<u8>, usize);
+///
+/// impl ErrorType for Flaky {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl embedded_io_async::Write for Flaky {
+///     async fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorKind> {
+///         let n = buf.len().min(self.1);
+///         if n == 0 {
+///             return Err(ErrorKind::Other);
+///         }
+///         self.0.extend_from_slice(&buf[..n]);
+///         self.1 -= n;
+///         Ok(n)
+///     }
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], Flaky(Vec::new(), 3));
+/// write!(buf, "0123456789").unwrap();
+/// assert!(embedded_io_async::Write::flush(&mut buf).await.is_err());
+/// assert_eq!(buf.len(), 7);
+///
+/// buf.sink_mut().1 = usize::MAX;
+/// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+/// assert_eq!(buf.total_flushed(), 10);
+/// assert_eq!(buf.into_sink().0, b"0123456789");
+/// # })
+/// ```
+///
+/// Through the `embedded-io` blanket impls `&mut AwriteBuf` is a sync and async
+/// `Write` as well and can be lent to generic code:
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::AwriteBuf;
+///
+/// fn stage<W: embedded_io::Write>(mut w: W) {
+///     w.write_all(b"sync ").unwrap();
+/// }
+///
+/// async fn log<W: embedded_io_async::Write>(mut w: W) {
+///     w.write_all(b"async").await.unwrap();
+///     w.flush().await.unwrap();
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+/// stage(&mut buf);
+/// log(&mut buf).await;
+/// assert_eq!(buf.sink_ref(), b"sync async");
+/// # })
+/// ```
+impl<T: Scratch, U: embedded_io_async::Write> embedded_io_async::Write for AwriteBuf<T, U> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written = embedded_io::Write::write(self, buf)?;
+        if let Some(byte) = self.config.flush_on {
+            if let Some(i) = self.buf.as_ref()[..self.pos]
+                .iter()
+                .rposition(|b| *b == byte)
+            {
+                self.drain(i + 1).await?;
+                self.flushed(i + 1);
+            }
+        }
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.pos < self.config.min_flush {
+            return Ok(());
+        }
+        self.force_flush().await
+    }
+}
+
+impl<T: Scratch, U: embedded_io::Write> AwriteBuf<T, U> {
+    /// Flush the scratch to a blocking sink.
+    ///
+    /// Behaves like [`Self::force_flush`]: retries, the line prefix and indentation,
+    /// UTF-8 boundaries, sticky errors and the flush hook all apply.
+    /// Sink errors are reported as `Error::Async` like in the async flush.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut sink = Vec::<u8>::new();
+    /// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+    /// buf.set_line_prefix(b"> ");
+    /// write!(buf, "sync\nblock").unwrap();
+    /// buf.flush_sync().unwrap();
+    /// assert!(buf.is_empty());
+    /// assert_eq!(sink, b"sync\n> block");
+    /// ```
+    pub fn flush_sync(&mut self) -> Result<(), Error<U::Error>> {
+        let mut view = AwriteBuf {
+            buf: &mut self.buf,
+            sink: Blocking(&mut self.sink),
+            pos: self.pos,
+            total: self.total,
+            generation: self.generation,
+            since: self.since,
+            depth: self.depth,
+            latched: self.latched,
+            config: self.config,
+        };
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let ret = match core::pin::pin!(view.force_flush()).poll(&mut cx) {
+            core::task::Poll::Ready(ret) => ret,
+            core::task::Poll::Pending => unreachable!("blocking sink never pends"),
+        };
+        self.pos = view.pos;
+        self.total = view.total;
+        self.since = view.since;
+        self.latched = view.latched;
+        ret
+    }
+}
+
+// A blocking sink in the async flush path, ready on the first poll
+struct Blocking<'a, U>(&'a mut U);
+
+impl<U: ErrorType> ErrorType for Blocking<'_, U> {
+    type Error = U::Error;
+}
+
+impl<U: embedded_io::Write> embedded_io_async::Write for Blocking<'_, U> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0.write(buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> AwriteBuf<T, U> {
+    // Write the first `n` buffered bytes to the sink and move the rest to the front.
+    // On error, only what the sink did not accept stays buffered.
+    async fn drain(&mut self, n: usize) -> Result<(), Error<U::Error>> {
+        self.check_latched()?;
+        let mut sent = 0;
+        let mut retries = self.config.retries;
+        let indent = self.depth.saturating_mul(self.config.indent_unit);
+        let lines = !self.config.line_prefix.is_empty() || indent > 0;
+        let mut start_line = indent > 0 && n > 0 && self.buf.as_ref()[0] != b'\n';
+        let ret = loop {
+            if sent == n {
+                break Ok(());
+            }
+            if core::mem::take(&mut start_line) {
+                if let Err(e) = self.write_spaces(indent).await {
+                    break Err(Error::Async(e));
+                }
+            }
+            let buf = &self.buf.as_ref()[..n];
+            let end = if !lines {
+                n
+            } else {
+                buf[sent..]
+                    .iter()
+                    .position(|b| *b == b'\n')
+                    .map_or(n, |i| sent + i + 1)
+            };
+            match self.sink.write(&buf[sent..end]).await {
+                Ok(0) => panic!("write() returned Ok(0)"),
+                Ok(written) => {
+                    sent += written;
+                    let buf = self.buf.as_ref();
+                    if lines && sent < n && buf[sent - 1] == b'\n' && buf[sent] != b'\n' {
+                        if let Err(e) = self.sink.write_all(self.config.line_prefix).await {
+                            break Err(Error::Async(e));
+                        }
+                        start_line = indent > 0;
+                    }
+                }
+                Err(e)
+                    if retries > 0
+                        && embedded_io::Error::kind(&e) == embedded_io::ErrorKind::Interrupted =>
+                {
+                    retries -= 1;
+                }
+                Err(e) => break Err(Error::Async(e)),
+            }
+        };
+        self.consume(sent);
+        self.latch(ret)
+    }
+
+    // No setting needs the bytes to pass through the scratch
+    fn direct(&self) -> bool {
+        !self.config.crlf
+            && !self.config.utf8
+            && self.config.line_prefix.is_empty()
+            && self.depth.saturating_mul(self.config.indent_unit) == 0
+    }
+
+    // Write `buf` to the sink bypassing the empty scratch, like a flush of it
+    async fn write_direct(&mut self, buf: &[u8]) -> Result<(), Error<U::Error>> {
+        self.check_latched()?;
+        let mut sent = 0;
+        let mut retries = self.config.retries;
+        while sent < buf.len() {
+            match self.sink.write(&buf[sent..]).await {
+                Ok(0) => panic!("write() returned Ok(0)"),
+                Ok(written) => {
+                    sent += written;
+                    self.total += written as u64;
+                }
+                Err(e)
+                    if retries > 0
+                        && embedded_io::Error::kind(&e) == embedded_io::ErrorKind::Interrupted =>
+                {
+                    retries -= 1;
+                }
+                Err(e) => return self.latch(Err(Error::Async(e))),
+            }
+        }
+        if !self.config.skip_sink_flush {
+            let ret = self.sink.flush().await.map_err(Error::Async);
+            self.latch(ret)?;
+        }
+        self.flushed(buf.len());
+        Ok(())
+    }
+
+    async fn write_spaces(&mut self, mut len: usize) -> Result<(), U::Error> {
+        const SPACES: [u8; 16] = [b' '; 16];
+        while len > 0 {
+            let chunk = len.min(SPACES.len());
+            self.sink.write_all(&SPACES[..chunk]).await?;
+            len -= chunk;
+        }
+        Ok(())
+    }
+
+    /// Flush, committing each write as soon as the sink accepts it.
+    ///
+    /// If the future of [`embedded_io_async::Write::flush`] is dropped, bytes the
+    /// sink already accepted stay buffered and are sent again by the next flush.
+    /// Dropping this future instead keeps only the bytes not yet accepted, so a
+    /// resumed flush never duplicates output.
+    /// The price is moving the remainder to the front after every partial write.
+    ///
+    /// Sticky errors, retries, UTF-8 boundaries, the sink flush and the flush hook
+    /// apply like in the async flush. The line prefix and indentation do not:
+    /// every write is a slice of the scratch as it is, so a cut short write
+    /// leaves no half-written prefix behind.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::{pin::pin, task::Poll};
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// // Takes three bytes per write, then stalls when out of budget
+    /// #[derive(Default)]
+    /// struct Slow {
+    ///     data: Vec<u8>,
+    ///     budget: usize,
+    /// }
+    ///
+    /// impl embedded_io::ErrorType for Slow {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Slow {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         let budget = self.budget;
+    ///         core::future::poll_fn(|_| if budget == 0 { Poll::Pending } else { Poll::Ready(()) })
+    ///             .await;
+    ///         self.budget -= 1;
+    ///         let n = buf.len().min(3);
+    ///         self.data.extend_from_slice(&buf[..n]);
+    ///         Ok(n)
+    ///     }
+    /// }
+    ///
+    /// let sink = Slow { budget: 1, ..Default::default() };
+    /// let mut buf = AwriteBuf::new([0u8; 16], sink);
+    /// write!(buf, "no dupes").unwrap();
+    /// {
+    ///     let mut flush = pin!(buf.flush_cancel_safe());
+    ///     assert!(futures::poll!(flush.as_mut()).is_pending());
+    /// }
+    /// assert_eq!(buf.written(), b"dupes");
+    ///
+    /// buf.sink_mut().budget = usize::MAX;
+    /// buf.flush_cancel_safe().await.unwrap();
+    /// assert_eq!(buf.sink_ref().data, b"no dupes");
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.set_utf8_boundaries(true);
+    /// buf.write_all(&"µs".as_bytes()[..1]).unwrap();
+    /// buf.flush_cancel_safe().await.unwrap();
+    /// assert_eq!(buf.len(), 1);
+    /// # })
+    /// ```
+    pub async fn flush_cancel_safe(&mut self) -> Result<(), Error<U::Error>> {
+        self.check_latched()?;
+        let n = self.flushable();
+        let mut sent = 0;
+        let mut retries = self.config.retries;
+        while sent < n {
+            match self.sink.write(&self.buf.as_ref()[..n - sent]).await {
+                Ok(0) => panic!("write() returned Ok(0)"),
+                Ok(written) => {
+                    self.consume(written);
+                    sent += written;
+                }
+                Err(e)
+                    if retries > 0
+                        && embedded_io::Error::kind(&e) == embedded_io::ErrorKind::Interrupted =>
+                {
+                    retries -= 1;
+                }
+                Err(e) => return self.latch(Err(Error::Async(e))),
+            }
+        }
+        if !self.config.skip_sink_flush {
+            let ret = self.sink.flush().await.map_err(Error::Async);
+            self.latch(ret)?;
+        }
+        self.flushed(n);
+        Ok(())
+    }
+
+    /// Flush regardless of [`AwriteBuf::set_min_flush`].
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.set_min_flush(4);
+    /// write!(buf, "ab").unwrap();
+    /// aflush!(buf).unwrap();
+    /// assert!(buf.sink_ref().is_empty());
+    /// write!(buf, "cd").unwrap();
+    /// aflush!(buf).unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abcd");
+    ///
+    /// write!(buf, "e").unwrap();
+    /// buf.force_flush().await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abcde");
+    ///
+    /// // The threshold still applies
+    /// write!(buf, "f").unwrap();
+    /// aflush!(buf).unwrap();
+    /// assert_eq!(buf.written(), b"f");
+    /// # })
+    /// ```
+    pub async fn force_flush(&mut self) -> Result<(), Error<U::Error>> {
+        self.flush_n(self.flushable()).await
+    }
+
+    // Flush the first `n` buffered bytes, then the sink
+    async fn flush_n(&mut self, n: usize) -> Result<(), Error<U::Error>> {
+        self.drain(n).await?;
+        if !self.config.skip_sink_flush {
+            let ret = self.sink.flush().await.map_err(Error::Async);
+            self.latch(ret)?;
+        }
+        self.flushed(n);
+        Ok(())
+    }
+
+    /// Flush without an executor, e.g. on startup or panic paths.
+    ///
+    /// Polls [`Self::force_flush`] with a no-op waker until it completes, so
+    /// [`Self::set_min_flush`] does not hold back the last bytes. A sink that is
+    /// pending is polled again right away, i.e. this busy-waits. For sinks that
+    /// make progress when polled, like a UART that is polled for room.
+    ///
+    /// # Panics
+    ///
+    /// If the flush is still `Pending` after 65536 polls, e.g. because the sink
+    /// waits to be woken by an interrupt or another task.
+    ///
+    /// ```
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.set_min_flush(16);
+    /// write!(buf, "panicked").unwrap();
+    /// buf.flush_blocking().unwrap();
+    /// assert_eq!(buf.sink_ref(), b"panicked");
+    /// ```
+    ///
+    /// ```should_panic
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// // Waits for a wakeup that never comes
+    /// struct Asleep;
+    ///
+    /// impl embedded_io::ErrorType for Asleep {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Asleep {
+    ///     async fn write(&mut self, _buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         core::future::pending().await
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Asleep);
+    /// write!(buf, "lost").unwrap();
+    /// buf.flush_blocking().ok();
+    /// ```
+    ///
+    /// ```
+    /// use core::task::Poll;
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// // Busy for a few polls per write, like a UART with a full FIFO
+    /// #[derive(Default)]
+    /// struct Uart(Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Uart {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Uart {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         let mut busy = 3;
+    ///         core::future::poll_fn(|_| {
+    ///             busy -= 1;
+    ///             if busy > 0 { Poll::Pending } else { Poll::Ready(()) }
+    ///         })
+    ///         .await;
+    ///         self.0.push(buf[0]);
+    ///         Ok(1)
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Uart::default());
+    /// write!(buf, "boot").unwrap();
+    /// buf.flush_blocking().unwrap();
+    /// assert_eq!(buf.sink_ref().0, b"boot");
+    /// ```
+    pub fn flush_blocking(&mut self) -> Result<(), Error<U::Error>> {
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let mut flush = core::pin::pin!(self.force_flush());
+        for _ in 0..1 << 16 {
+            if let core::task::Poll::Ready(ret) = flush.as_mut().poll(&mut cx) {
+                return ret;
+            }
+        }
+        panic!("flush_blocking: sink still pending")
+    }
+
+    /// Flush and take the sink.
+    ///
+    /// On error the buffer and the sink are dropped.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "last words").unwrap();
+    /// assert_eq!(buf.flush_and_into_sink().await.unwrap(), b"last words");
+    /// # })
+    /// ```
+    pub async fn flush_and_into_sink(mut self) -> Result<U, Error<U::Error>> {
+        self.flush_n(self.pos).await?;
+        Ok(self.sink)
+    }
+
+    /// Flush to the current sink, then continue with `new`.
+    ///
+    /// Returns the rewired buffer and the old sink.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{awrite, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "usb").unwrap();
+    /// let (mut buf, usb) = buf.replace_sink(Vec::<u8>::new()).await.unwrap();
+    /// awrite!(buf, "uart").unwrap();
+    ///
+    /// assert_eq!(usb, b"usb");
+    /// assert_eq!(buf.sink_ref(), b"uart");
+    /// # })
+    /// ```
+    pub async fn replace_sink<V: embedded_io_async::Write>(
+        mut self,
+        new: V,
+    ) -> Result<(AwriteBuf<T, V>, U), Error<U::Error>> {
+        self.flush_n(self.pos).await?;
+        let buf = AwriteBuf {
+            total: self.total,
+            generation: self.generation,
+            depth: self.depth,
+            config: self.config,
+            ..AwriteBuf::new(self.buf, new)
+        };
+        Ok((buf, self.sink))
+    }
+
+    /// Flush only the first `n` buffered bytes, keeping the rest buffered.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// write!(buf, "head+tail!").unwrap();
+    /// buf.flush_upto(4).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"head");
+    /// assert_eq!(buf.written(), b"+tail!");
+    ///
+    /// buf.flush_upto(100).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"head+tail!");
+    /// assert!(buf.is_empty());
+    /// # })
+    /// ```
+    pub async fn flush_upto(&mut self, n: usize) -> Result<(), Error<U::Error>> {
+        let n = n.min(self.pos);
+        self.drain(n).await?;
+        self.flushed(n);
+        Ok(())
+    }
+
+    /// Copy a string into the scratch and flush, bypassing `core::fmt`.
+    ///
+    /// Like `awrite!(buf, "{}", s)` but without pulling in the formatting machinery.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{astr, awrite, AwriteBuf};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// awrite!(buf, "prefix: ").unwrap();
+    /// let via_fmt = buf.sink_ref().clone();
+    /// buf.sink_mut().clear();
+    ///
+    /// buf.write_str_fast("prefix: ").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), &via_fmt);
+    /// astr!(buf, "x").unwrap();
+    /// assert_eq!(buf.sink_ref(), b"prefix: x");
+    /// assert!(astr!(buf, "this is too long!").is_err());
+    /// # })
+    /// ```
+    pub async fn write_str_fast(&mut self, s: &str) -> Result<(), Error<U::Error>> {
+        self.push_all(s.as_bytes()).map_err(Error::Sync)?;
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Format into the scratch and flush, yielding the number of bytes flushed.
+    ///
+    /// This is what [`awrite!`] expands to. It is not called `write_fmt` so that
+    /// `write!` keeps targeting the synchronous [`embedded_io::Write::write_fmt`].
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// assert_eq!(buf.awrite_fmt(format_args!("{}", 42)).await, Ok(2));
+    /// assert_eq!(buf.sink_ref(), b"42");
+    /// # })
+    /// ```
+    ///
+    /// Formatting happens on the call, only the flush is deferred to the future.
+    /// `Arguments` is neither `Send` nor `Sync`, so it must not be held across the await
+    /// for the future to be `Send`.
+    pub fn awrite_fmt(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+    ) -> impl Future<Output = Result<usize, embedded_io::WriteFmtError<Error<U::Error>>>> + '_ {
+        let start = self.pos;
+        let ret = embedded_io::Write::write_fmt(self, args);
+        if ret.is_err() && self.config.atomic {
+            self.pos = start;
+        }
+        async move {
+            ret?;
+            let len = self.pos;
+            embedded_io_async::Write::flush(self).await?;
+            Ok(len - self.pos)
+        }
+    }
+
+    /// Like [`Self::awrite_fmt`] but flushes the formatted prefix also on error.
+    ///
+    /// On a formatting or buffer error, whatever made it into the scratch is
+    /// flushed before the original error is returned. A failure of that flush
+    /// is dropped in favor of the original error.
+    /// With [`Self::set_atomic`] the partial write is rolled back and nothing is flushed.
+    /// See [`awrite_partial!`].
+    pub fn awrite_fmt_partial(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+    ) -> impl Future<Output = Result<usize, embedded_io::WriteFmtError<Error<U::Error>>>> + '_ {
+        let start = self.pos;
+        let ret = embedded_io::Write::write_fmt(self, args);
+        if ret.is_err() && self.config.atomic {
+            self.pos = start;
+        }
+        async move {
+            if let Err(err) = ret {
+                self.force_flush().await.ok();
+                return Err(err);
+            }
+            let len = self.pos;
+            embedded_io_async::Write::flush(self).await?;
+            Ok(len - self.pos)
+        }
+    }
+
+    /// Write all of `buf`, flushing to the sink whenever the scratch is full.
+    ///
+    /// Never fails with `SliceWriteError::Full` unless CRLF translation is on and
+    /// the scratch can't hold `\r\n`.
+    /// Whatever remains after the last flush stays buffered.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let msg: Vec<u8> = (0..200).collect();
+    /// let mut sink = Vec::<u8>::new();
+    /// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+    ///
+    /// buf.write_chunked(&msg).await.unwrap();
+    /// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+    ///
+    /// assert_eq!(sink, msg);
+    ///
+    /// // A `\n` expanding into the last free byte flushes first
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.write_chunked(b"abc\ndef").await.unwrap();
+    /// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abc\r\ndef");
+    /// # })
+    /// ```
+    ///
+    /// Input larger than the scratch can grow to is written to the sink directly
+    /// once the scratch is empty, like a flush of it: retries, sticky errors, the
+    /// sink flush and the flush hook apply. Not with CRLF translation, a line
+    /// prefix, indentation or UTF-8 boundaries, these go through the scratch.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::sync::atomic::{AtomicUsize, Ordering};
+    /// use awrite::AwriteBuf;
+    ///
+    /// static FLUSHED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// #[derive(Default)]
+    /// struct Count(Vec<usize>);
+    ///
+    /// impl embedded_io::ErrorType for Count {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Count {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         self.0.push(buf.len());
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Count::default());
+    /// buf.set_on_flush(Some(|n| {
+    ///     FLUSHED.fetch_add(n, Ordering::Relaxed);
+    /// }));
+    /// buf.write_chunked(&[0; 4096]).await.unwrap();
+    /// assert_eq!(buf.sink_ref().0, [4096]);
+    /// assert_eq!(buf.total_flushed(), 4096);
+    /// assert_eq!(FLUSHED.load(Ordering::Relaxed), 4096);
+    ///
+    /// // The line prefix needs the scratch
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// buf.set_line_prefix(b"> ");
+    /// buf.write_chunked(&b"ab\n".repeat(15)).await.unwrap();
+    /// buf.force_flush().await.unwrap();
+    /// assert!(buf.sink_ref().starts_with(b"ab\n> ab\n> ab\n"));
+    /// assert_eq!(buf.sink_ref().len(), 45 + 14 * 2);
+    /// # })
+    /// ```
+    pub async fn write_chunked(&mut self, mut buf: &[u8]) -> Result<(), Error<U::Error>> {
+        while !buf.is_empty() {
+            if self.pos == 0 && self.direct() && buf.len() > self.buf.max_capacity() {
+                return self.write_direct(buf).await;
+            }
+            if self.needs_room(buf) {
+                self.force_flush().await?;
+            }
+            let written = embedded_io::Write::write(self, buf)?;
+            buf = &buf[written..];
+        }
+        Ok(())
+    }
+
+    /// Write the slices of `bufs` in order, then flush.
+    ///
+    /// The scratch is flushed between slices only when full, see [`Self::write_chunked`].
+    /// An empty `bufs` does nothing.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.write_vectored(&[b"[hdr]", b"payload", b"\n"]).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"[hdr]payload\n");
+    /// assert!(buf.is_empty());
+    /// buf.write_vectored(&[]).await.unwrap();
+    /// assert_eq!(buf.total_flushed(), 13);
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.write_vectored(&[b"abc", b"\n"]).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abc\r\n");
+    /// # })
+    /// ```
+    pub async fn write_vectored(&mut self, bufs: &[&[u8]]) -> Result<(), Error<U::Error>> {
+        if bufs.is_empty() {
+            return Ok(());
+        }
+        for buf in bufs {
+            self.write_chunked(buf).await?;
+        }
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Flush if another `len` bytes would not fit.
+    ///
+    /// Fails with `SliceWriteError::Full` if `len` exceeds what the empty scratch can hold.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// write!(buf, "12345").unwrap();
+    /// buf.ensure_capacity(3).await.unwrap();
+    /// assert!(buf.sink_ref().is_empty());
+    /// buf.ensure_capacity(4).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"12345");
+    /// assert!(buf.ensure_capacity(9).await.is_err());
+    /// # })
+    /// ```
+    pub async fn ensure_capacity(&mut self, len: usize) -> Result<(), Error<U::Error>> {
+        if self.would_overflow(len) {
+            self.force_flush().await?;
+            if self.would_overflow(len) {
+                return Err(Error::Sync(embedded_io::SliceWriteError::Full));
+            }
+        }
+        Ok(())
+    }
+
+    /// Write `value` padded with `fill` to exactly `width` bytes, then flush.
+    ///
+    /// A value wider than `width` is cut to `width` with [`Overflow::Truncate`]
+    /// and fails with `SliceWriteError::Full` otherwise, leaving nothing buffered.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{Align, AwriteBuf, Overflow};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.write_padded(42, 5, b' ', Align::Left).await.unwrap();
+    /// buf.write_padded(7, 4, b'0', Align::Right).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"42   0007");
+    ///
+    /// assert!(buf.write_padded("abcdef", 4, b' ', Align::Left).await.is_err());
+    /// assert_eq!(buf.sink_ref(), b"42   0007");
+    ///
+    /// let mut buf = buf.with_overflow(Overflow::Truncate);
+    /// buf.write_padded("abcdef", 4, b' ', Align::Right).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"42   0007abcd");
+    /// # })
+    /// ```
+    pub async fn write_padded(
+        &mut self,
+        value: impl core::fmt::Display,
+        width: usize,
+        fill: u8,
+        align: Align,
+    ) -> Result<(), Error<U::Error>> {
+        self.ensure_capacity(width).await?;
+        let start = self.pos;
+        if let Err(e) = embedded_io::Write::write_fmt(self, format_args!("{value}")) {
+            self.pos = start;
+            return Err(e.into());
+        }
+        let len = self.pos - start;
+        if len > width && self.config.overflow == Overflow::Error {
+            self.pos = start;
+            return Err(Error::Sync(embedded_io::SliceWriteError::Full));
+        }
+        if len < width {
+            let pad = width - len;
+            self.buf.grow(start + width);
+            let buf = &mut self.buf.as_mut()[start..start + width];
+            match align {
+                Align::Left => buf[len..].fill(fill),
+                Align::Right => {
+                    buf.copy_within(..len, pad);
+                    buf[..pad].fill(fill);
+                }
+            }
+        }
+        self.pos = start + width;
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write raw bytes and flush, the binary counterpart to [`AwriteBuf::write_str_fast`].
+    ///
+    /// Input larger than the scratch is chunked, see [`AwriteBuf::write_chunked`].
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.write_bytes(&[0xff, 0x00, 0xc3]).await.unwrap();
+    /// buf.write_bytes(&[0x80; 6]).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), &[0xff, 0x00, 0xc3, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80]);
+    /// assert!(buf.is_empty());
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.write_bytes(b"abc\n").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abc\r\n");
+    /// # })
+    /// ```
+    pub async fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error<U::Error>> {
+        self.write_chunked(data).await?;
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write the low `bits` bits of `value` in binary and flush, without `core::fmt`.
+    ///
+    /// Digits are grouped from the right by `_` every `group` digits, `0` disables
+    /// grouping. More than 32 `bits` fail with `Error::Fmt`.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{AwriteBuf, Error};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.write_bin(0xa5, 8, 4).await.unwrap();
+    /// buf.write_bin(0b101, 5, 0).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"1010_010100101");
+    /// assert_eq!(buf.write_bin(0, 33, 4).await, Err(Error::Fmt));
+    /// # })
+    /// ```
+    pub async fn write_bin(
+        &mut self,
+        value: u32,
+        bits: u8,
+        group: u8,
+    ) -> Result<(), Error<U::Error>> {
+        if bits > 32 {
+            return Err(Error::Fmt);
+        }
+        for i in (0..bits).rev() {
+            if self.remaining() == 0 {
+                self.force_flush().await?;
+            }
+            let digit = b'0' + ((value >> i) & 1) as u8;
+            self.push_all(&[digit]).map_err(Error::Sync)?;
+            if group > 0 && i > 0 && i % group == 0 {
+                if self.remaining() == 0 {
+                    self.force_flush().await?;
+                }
+                self.push_all(b"_").map_err(Error::Sync)?;
+            }
+        }
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write a duration of `ms` milliseconds as `HH:MM:SS.mmm` and flush.
+    ///
+    /// Hours are not wrapped at 24 and grow beyond two digits as needed.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.write_duration_ms(0).await.unwrap();
+    /// buf.write_duration_ms(7).await.unwrap();
+    /// buf.write_duration_ms(3_723_004).await.unwrap();
+    /// buf.write_duration_ms(100 * 3_600_000 + 59_999).await.unwrap();
+    /// assert_eq!(
+    ///     buf.sink_ref(),
+    ///     b"00:00:00.00000:00:00.00701:02:03.004100:00:59.999"
+    /// );
+    /// # })
+    /// ```
+    pub async fn write_duration_ms(&mut self, ms: u64) -> Result<(), Error<U::Error>> {
+        let (s, ms) = (ms / 1000, ms % 1000);
+        let (m, s) = (s / 60, s % 60);
+        let (h, m) = (m / 60, m % 60);
+        self.awrite_fmt(format_args!("{h:02}:{m:02}:{s:02}.{ms:03}"))
+            .await?;
+        Ok(())
+    }
+
+    /// Write the fixed-point value `raw / 2^frac_bits` with `decimals` fractional digits and flush.
+    ///
+    /// Scaling uses integer math only and rounds half away from zero at the last digit.
+    /// Negative values that do not round to zero get a leading `-`.
+    /// More than 32 `frac_bits` or 18 `decimals` fail with `Error::Fmt`.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{AwriteBuf, Error};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// // Q15
+    /// for raw in [16384, -12345, 32767, -8] {
+    ///     buf.write_fixed_point(raw, 15, 3).await.unwrap();
+    ///     buf.write_bytes(b" ").await.unwrap();
+    /// }
+    /// buf.write_fixed_point(-3 << 16 | 0x8000, 16, 0).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"0.500 -0.377 1.000 0.000 -3");
+    /// assert_eq!(buf.write_fixed_point(1, 33, 0).await, Err(Error::Fmt));
+    /// # })
+    /// ```
+    pub async fn write_fixed_point(
+        &mut self,
+        raw: i32,
+        frac_bits: u8,
+        decimals: u8,
+    ) -> Result<(), Error<U::Error>> {
+        if frac_bits > 32 || decimals > 18 {
+            return Err(Error::Fmt);
+        }
+        let pow = 10u64.pow(decimals as u32);
+        let mut scaled = raw.unsigned_abs() as u128 * pow as u128;
+        if frac_bits > 0 {
+            scaled = (scaled + (1 << (frac_bits - 1))) >> frac_bits;
+        }
+        let sign = if raw < 0 && scaled != 0 { "-" } else { "" };
+        let (int, frac) = (scaled / pow as u128, scaled % pow as u128);
+        if decimals == 0 {
+            self.awrite_fmt(format_args!("{sign}{int}")).await?;
+        } else {
+            let width = decimals as usize;
+            self.awrite_fmt(format_args!("{sign}{int}.{frac:0width$}"))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Write `bytes` as lowercase hex digit pairs and flush, without `core::fmt`.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let bytes: Vec<u8> = (0..64).map(|i| i * 4).collect();
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.write_hex(&bytes).await.unwrap();
+    ///
+    /// let reference: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    /// assert_eq!(buf.sink_ref(), reference.as_bytes());
+    /// # })
+    /// ```
+    pub async fn write_hex(&mut self, bytes: &[u8]) -> Result<(), Error<U::Error>> {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        for b in bytes {
+            if self.remaining() < 2 {
+                self.force_flush().await?;
+            }
+            let pair = [HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]];
+            self.push_all(&pair).map_err(Error::Sync)?;
+        }
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write a canonical hexdump of `data` and flush, without `core::fmt`.
+    ///
+    /// Each row holds 16 bytes, preceded by the offset from `base` in at least
+    /// four hex digits and followed by an ASCII gutter with non-printables as `.`.
+    /// A final partial row is padded so the gutter aligns.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// buf.hexdump(b"Hello, hexdump!\n\0\x01\xffz", 0x100).await.unwrap();
+    /// assert_eq!(
+    ///     core::str::from_utf8(buf.sink_ref()).unwrap(),
+    ///     "0100: 48 65 6c 6c 6f 2c 20 68 65 78 64 75 6d 70 21 0a |Hello, hexdump!.|\n\
+    ///      0110: 00 01 ff 7a                                     |...z|\n"
+    /// );
+    ///
+    /// // The row's `\n` lands on the last byte of the scratch
+    /// let mut buf = AwriteBuf::new([0u8; 29], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.hexdump(b"A", 0).await.unwrap();
+    /// assert!(buf.sink_ref().ends_with(b" |A|\r\n"));
+    /// # })
+    /// ```
+    pub async fn hexdump(&mut self, data: &[u8], base: usize) -> Result<(), Error<U::Error>> {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        // Offset, hex columns, gutter
+        let mut row = [b' '; 2 * size_of::<usize>() + 1 + 16 * 3 + 2 + 16 + 2];
+        for (i, chunk) in data.chunks(16).enumerate() {
+            let offset = base.wrapping_add(i * 16);
+            let digits = (usize::BITS - offset.leading_zeros()).div_ceil(4).max(4) as usize;
+            for (k, d) in row[..digits].iter_mut().enumerate() {
+                *d = HEX[(offset >> (4 * (digits - 1 - k))) & 0xf];
+            }
+            row[digits] = b':';
+            let hex = &mut row[digits + 1..digits + 1 + 16 * 3];
+            hex.fill(b' ');
+            for (col, b) in hex.chunks_mut(3).zip(chunk) {
+                col[1] = HEX[(b >> 4) as usize];
+                col[2] = HEX[(b & 0xf) as usize];
+            }
+            let mut len = digits + 1 + 16 * 3;
+            row[len..len + 2].copy_from_slice(b" |");
+            len += 2;
+            for b in chunk {
+                row[len] = if b.is_ascii_graphic() || *b == b' ' {
+                    *b
+                } else {
+                    b'.'
+                };
+                len += 1;
+            }
+            row[len..len + 2].copy_from_slice(b"|\n");
+            len += 2;
+            self.write_chunked(&row[..len]).await?;
+        }
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write `byte` `count` times, flushing whenever the scratch fills, then flush.
+    ///
+    /// The scratch is filled in bulk. A `count` of zero does not flush.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// buf.write_repeated(0xff, 1000).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), &[0xff; 1000]);
+    ///
+    /// buf.write_repeated(0, 0).await.unwrap();
+    /// assert_eq!(buf.total_flushed(), 1000);
+    /// # })
+    /// ```
+    pub async fn write_repeated(
+        &mut self,
+        byte: u8,
+        mut count: usize,
+    ) -> Result<(), Error<U::Error>> {
+        if count == 0 {
+            return Ok(());
+        }
+        // Let growable scratches take it all
+        self.buf.grow(self.pos + count);
+        let expand = self.config.crlf && byte == b'\n';
+        while count > 0 {
+            self.ensure_capacity(if expand { 2 } else { 1 }).await?;
+            let n = if expand {
+                self.push(b"\n").map_err(Error::Sync)?
+            } else {
+                let n = count.min(self.remaining());
+                self.buf.grow(self.pos + n);
+                self.buf.as_mut()[self.pos..self.pos + n].fill(byte);
+                self.pos += n;
+                n
+            };
+            count -= n;
+        }
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Repeat `pattern` for exactly `total_len` bytes, flushing whenever the scratch fills, then flush.
+    ///
+    /// The last repetition is cut short as needed, possibly within a UTF-8 sequence.
+    /// An empty `pattern` fails with `Error::Fmt`. A `total_len` of zero does not flush.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{AwriteBuf, Error};
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.write_fill("=-=", 10).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"=-==-==-==");
+    /// buf.write_fill("-", 0).await.unwrap();
+    /// assert_eq!(buf.write_fill("", 1).await, Err(Error::Fmt));
+    /// assert_eq!(buf.total_flushed(), 10);
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 3], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.write_fill("ab\n", 6).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"ab\r\nab\r\n");
+    /// # })
+    /// ```
+    pub async fn write_fill(
+        &mut self,
+        pattern: &str,
+        total_len: usize,
+    ) -> Result<(), Error<U::Error>> {
+        if pattern.is_empty() {
+            return Err(Error::Fmt);
+        }
+        if total_len == 0 {
+            return Ok(());
+        }
+        let mut left = total_len;
+        while left > 0 {
+            let n = left.min(pattern.len());
+            self.write_chunked(&pattern.as_bytes()[..n]).await?;
+            left -= n;
+        }
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write a table row of left aligned `(text, width)` cells, then flush.
+    ///
+    /// Cells are separated by a single space and the row ends with `\n`.
+    /// Widths count `char`s. Text is padded with spaces to its width, except
+    /// in the last cell to avoid trailing whitespace. Over-wide text is cut
+    /// hard at its width, without an ellipsis. An empty `cells` writes just `\n`.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.write_row(&[("name", 6), ("temp", 5), ("state", 8)]).await.unwrap();
+    /// buf.write_row(&[("adc0", 6), ("23.5°", 5), ("ok", 8)]).await.unwrap();
+    /// buf.write_row(&[("thermistor", 6), ("-40.25", 5), ("overrange", 8)])
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     core::str::from_utf8(buf.sink_ref()).unwrap(),
+    ///     "name   temp  state\n\
+    ///      adc0   23.5° ok\n\
+    ///      thermi -40.2 overrang\n"
+    /// );
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.write_row(&[("abc", 3)]).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abc\r\n");
+    /// # })
+    /// ```
+    pub async fn write_row(&mut self, cells: &[(&str, usize)]) -> Result<(), Error<U::Error>> {
+        const SPACES: [u8; 16] = [b' '; 16];
+        for (i, &(text, width)) in cells.iter().enumerate() {
+            if i > 0 {
+                self.write_chunked(b" ").await?;
+            }
+            let end = text
+                .char_indices()
+                .nth(width)
+                .map_or(text.len(), |(k, _)| k);
+            let cell = &text[..end];
+            self.write_chunked(cell.as_bytes()).await?;
+            if i + 1 < cells.len() {
+                let mut pad = width - cell.chars().count();
+                while pad > 0 {
+                    let n = pad.min(SPACES.len());
+                    self.write_chunked(&SPACES[..n]).await?;
+                    pad -= n;
+                }
+            }
+        }
+        self.write_chunked(b"\n").await?;
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write `s` as a quoted and escaped JSON string, flushing whenever the scratch fills, then flush.
+    ///
+    /// `"` and `\` are backslash escaped, `\n`, `\r` and `\t` use their short
+    /// escapes and other bytes below 0x20 become `\u00XX`.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.write_json_str("say \"hi\"\\\n\x01µ").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), r#""say \"hi\"\\\n\u0001µ""#.as_bytes());
+    /// # })
+    /// ```
+    pub async fn write_json_str(&mut self, s: &str) -> Result<(), Error<U::Error>> {
+        if self.remaining() == 0 {
+            self.force_flush().await?;
+        }
+        self.push_all(b"\"").map_err(Error::Sync)?;
+        for &b in s.as_bytes() {
+            let (esc, len) = json::escape(b);
+            if self.remaining() < len {
+                self.force_flush().await?;
+            }
+            self.push_all(&esc[..len]).map_err(Error::Sync)?;
+        }
+        if self.remaining() == 0 {
+            self.force_flush().await?;
+        }
+        self.push_all(b"\"").map_err(Error::Sync)?;
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Write `v` as a JSON number and flush.
+    ///
+    /// Finite values use the shortest round-trip form, which is valid JSON: no
+    /// trailing `.`, integers without fraction. Magnitudes of at least `1e21` or
+    /// below `1e-6` get an exponent, like in JavaScript's `JSON.stringify`.
+    /// `NaN` and infinities are `null`. See also [`JsonValue`].
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 32], Vec::<u8>::new());
+    /// for v in [5.0, -0.25, 1e-6, 1e-7, 1.5e300, -1e-300, f64::NAN, f64::NEG_INFINITY] {
+    ///     buf.write_json_f64(v).await.unwrap();
+    ///     buf.write_bytes(b",").await.unwrap();
+    /// }
+    /// assert_eq!(
+    ///     buf.sink_ref(),
+    ///     b"5,-0.25,0.000001,1e-7,1.5e300,-1e-300,null,null,"
+    /// );
+    /// # })
+    /// ```
+    pub async fn write_json_f64(&mut self, v: f64) -> Result<(), Error<U::Error>> {
+        if !v.is_finite() {
+            self.awrite_fmt(format_args!("null")).await?;
+        } else if json::exponent(v) {
+            self.awrite_fmt(format_args!("{v:e}")).await?;
+        } else {
+            self.awrite_fmt(format_args!("{v}")).await?;
+        }
+        Ok(())
+    }
+
+    /// Write the bytes of an iterator, flushing whenever the scratch fills, then flush.
+    ///
+    /// An empty iterator does not flush.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// buf.write_iter((0..100).map(|i| i as u8)).await.unwrap();
+    /// assert!(buf.sink_ref().iter().copied().eq(0..100));
+    /// assert!(buf.is_empty());
+    ///
+    /// buf.write_iter([]).await.unwrap();
+    /// assert_eq!(buf.total_flushed(), 100);
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.write_iter(*b"abc\n").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abc\r\n");
+    /// # })
+    /// ```
+    pub async fn write_iter(
+        &mut self,
+        it: impl IntoIterator<Item = u8>,
+    ) -> Result<(), Error<U::Error>> {
+        let mut any = false;
+        for b in it {
+            if self.needs_room(&[b]) {
+                self.force_flush().await?;
+            }
+            embedded_io::Write::write(self, &[b])?;
+            any = true;
+        }
+        if any {
+            embedded_io_async::Write::flush(self).await?;
+        }
+        Ok(())
+    }
+
+    /// Write the items separated by `sep`, flushing whenever the scratch fills, then flush.
+    ///
+    /// Each item must fit the scratch. An empty iterator does not flush.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.write_joined([1, 22, 333, 4444], ", ").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"1, 22, 333, 4444");
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.write_joined(["one"], ", ").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"one");
+    ///
+    /// buf.write_joined(core::iter::empty::<u8>(), ", ").await.unwrap();
+    /// assert_eq!(buf.total_flushed(), 3);
+    /// # })
+    /// ```
+    pub async fn write_joined<I, T2>(&mut self, items: I, sep: &str) -> Result<(), Error<U::Error>>
+    where
+        I: IntoIterator<Item = T2>,
+        T2: core::fmt::Display,
+    {
+        let mut first = true;
+        for item in items {
+            if !first {
+                self.write_chunked(sep.as_bytes()).await?;
+            }
+            first = false;
+            let start = self.pos;
+            if embedded_io::Write::write_fmt(self, format_args!("{item}")).is_err() {
+                self.pos = start;
+                self.force_flush().await?;
+                embedded_io::Write::write_fmt(self, format_args!("{item}"))?;
+            }
+        }
+        if !first {
+            embedded_io_async::Write::flush(self).await?;
+        }
+        Ok(())
+    }
+
+    /// View as the [`embedded_io_async::Write`] that flushes to the sink.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// // Both traits in scope, the view picks one
+    /// use embedded_io::Write as _;
+    /// use embedded_io_async::Write as _;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.as_async().write(b"async").await.unwrap();
+    /// buf.as_async().flush().await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"async");
+    /// # })
+    /// ```
+    pub fn as_async(&mut self) -> &mut impl embedded_io_async::Write<Error = Error<U::Error>> {
+        self
+    }
+
+    /// Buffer as much of `buf` as fits, flushing first if the scratch is full.
+    ///
+    /// Returns the number of bytes accepted.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::task::Poll;
+    /// use awrite::AwriteBuf;
+    ///
+    /// // Sink that is busy once per write
+    /// #[derive(Default)]
+    /// struct Slow(Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Slow {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Slow {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         let mut busy = true;
+    ///         core::future::poll_fn(|cx| {
+    ///             if core::mem::take(&mut busy) {
+    ///                 cx.waker().wake_by_ref();
+    ///                 Poll::Pending
+    ///             } else {
+    ///                 Poll::Ready(())
+    ///             }
+    ///         })
+    ///         .await;
+    ///         self.0.extend_from_slice(buf);
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Slow::default());
+    /// assert_eq!(buf.try_write_backpressured(b"abcdef"), Poll::Ready(Ok(4)));
+    /// // Full: a real-time caller may drop the data here
+    /// assert!(buf.try_write_backpressured(b"ef").is_pending());
+    ///
+    /// assert_eq!(buf.write_backpressured(b"ef").await, Ok(2));
+    /// assert_eq!(buf.sink_ref().0, b"abcd");
+    /// assert_eq!(buf.written(), b"ef");
+    /// # })
+    /// ```
+    pub async fn write_backpressured(&mut self, buf: &[u8]) -> Result<usize, Error<U::Error>> {
+        if self.is_full() && !buf.is_empty() {
+            self.force_flush().await?;
+        }
+        Ok(self.try_write(buf)?.written)
+    }
+
+    /// Pass text formatted elsewhere, e.g. in a `core::fmt::Write` buffer, to the sink.
+    ///
+    /// Chunks through the scratch like [`AwriteBuf::write_chunked`], then flushes.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::fmt::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut log = String::new();
+    /// write!(log, "{:?} ", [1, 2, 3]).unwrap();
+    /// write!(log, "done").unwrap();
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.flush_fmt_buffer(&log).await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"[1, 2, 3] done");
+    /// assert!(buf.is_empty());
+    /// # })
+    /// ```
+    pub async fn flush_fmt_buffer(&mut self, s: &impl AsRef<str>) -> Result<(), Error<U::Error>> {
+        self.write_chunked(s.as_ref().as_bytes()).await?;
+        embedded_io_async::Write::flush(self).await
+    }
+
+    /// Accept all of `buf` into the scratch or the sink, or fail.
+    ///
+    /// Unlike the trait `write_all()` this flushes to make room instead of
+    /// failing with `SliceWriteError::Full`. See [`AwriteBuf::write_chunked`].
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    /// buf.write_all_buffered(b"0123456789").await.unwrap();
+    /// buf.write_all_buffered(b"abc").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"0123456789");
+    /// assert_eq!(buf.written(), b"abc");
+    /// # })
+    /// ```
+    pub async fn write_all_buffered(&mut self, buf: &[u8]) -> Result<(), Error<U::Error>> {
+        self.write_chunked(buf).await
+    }
+
+    /// Run `f` against the buffer and flush afterwards.
+    ///
+    /// The flush runs even if `f` yields an error value. It does not run if `f`
+    /// panics or the future is dropped before completion. See also [`Self::guard`].
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    /// let n = buf
+    ///     .scope(async |b| {
+    ///         write!(b, "a").unwrap();
+    ///         write!(b, "b").unwrap();
+    ///         b.len()
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(n, 2);
+    /// assert_eq!(buf.sink_ref(), b"ab");
+    /// # })
+    /// ```
+    pub async fn scope<F, R>(&mut self, f: F) -> Result<R, Error<U::Error>>
+    where
+        F: AsyncFnOnce(&mut Self) -> R,
+    {
+        let ret = f(self).await;
+        embedded_io_async::Write::flush(self).await?;
+        Ok(ret)
+    }
+
+    /// Write a log message, flushing whenever the scratch fills or a line ends.
+    ///
+    /// Lines longer than the scratch are split instead of failing.
+    /// A trailing partial line stays buffered.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+    ///
+    /// buf.write_log("a very long line that does not fit\n").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"a very long line that does not fit\n");
+    /// assert!(buf.is_empty());
+    ///
+    /// buf.sink_mut().clear();
+    /// buf.write_log("one\ntwo\npart").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"one\ntwo\n");
+    /// assert_eq!(buf.written(), b"part");
+    ///
+    /// buf.write_log("\n").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"one\ntwo\npart\n");
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+    /// buf.set_crlf(true);
+    /// buf.write_log("abc\n").await.unwrap();
+    /// assert_eq!(buf.sink_ref(), b"abc\r\n");
+    /// # })
+    /// ```
+    pub async fn write_log(&mut self, s: &str) -> Result<(), Error<U::Error>> {
+        let mut buf = s.as_bytes();
+        while !buf.is_empty() {
+            if self.needs_room(buf) {
+                self.force_flush().await?;
+            }
+            let line = buf
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(buf.len(), |i| i + 1);
+            let written = embedded_io::Write::write(self, &buf[..line])?;
+            if buf[..written].last() == Some(&b'\n') {
+                embedded_io_async::Write::flush(self).await?;
+            }
+            buf = &buf[written..];
+        }
+        Ok(())
+    }
+}
+
+/// Format into the scratch and flush it to the sink.
+///
+/// Yields the number of bytes flushed: `Result<usize, WriteFmtError<Error<E>>>`.
+#[macro_export]
+macro_rules! awrite {
+    ($aw:expr, $($tt:tt)*) => {
+        $aw.awrite_fmt(format_args!($($tt)*)).await
+    };
+}
+
+/// Like [`awrite!`] with a trailing newline.
+#[macro_export]
+macro_rules! awriteln {
+    ($aw:expr $(,)?) => {
+        $crate::awrite!($aw, "\n")
+    };
+    ($aw:expr, $($tt:tt)*) => {
+        $aw.awrite_fmt(format_args!("{}\n", format_args!($($tt)*))).await
+    };
+}
+
+/// Like [`awrite!`] but formatting with `ufmt::uwrite!`.
+///
+/// Yields the number of bytes flushed: `Result<usize, Error<E>>`.
+/// The calling crate needs `ufmt` as a dependency.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{auwrite, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+/// assert_eq!(auwrite!(buf, "n={}", 42u32), Ok(4));
+/// ufmt::uwrite!(buf, "{}", -1i8).unwrap();
+/// assert_eq!(auwrite!(&mut buf, " {:?}", true), Ok(7));
+/// assert_eq!(buf.sink_ref(), b"n=42-1 true");
+///
+/// assert_eq!(
+///     auwrite!(buf, "this is too long"),
+///     Err(awrite::Error::Sync(embedded_io::SliceWriteError::Full))
+/// );
+/// # })
+/// ```
+#[cfg(feature = "ufmt")]
+#[macro_export]
+macro_rules! auwrite {
+    ($aw:expr, $($tt:tt)*) => {
+        $aw.auwrite_with(|w| ::ufmt::uwrite!(w, $($tt)*)).await
+    };
+}
+
+/// Like [`awrite!`] but evaluates `$or` on error instead of yielding a `Result`.
+///
+/// `$or` can divert control flow, e.g. `return`, `break` or `continue`.
+/// On success yields the number of bytes flushed.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite_or, AwriteBuf};
+///
+/// async fn log(buf: &mut AwriteBuf<[u8; 4], Vec<u8>>) {
+///     awrite_or!(buf, return, "ok ");
+///     awrite_or!(buf, return, "too long");
+///     unreachable!();
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 4], Vec::<u8>::new());
+/// log(&mut buf).await;
+/// assert_eq!(buf.sink_ref(), b"ok ");
+///
+/// buf.clear();
+/// assert_eq!(awrite_or!(buf, 0, "{}", 12), 2);
+/// # })
+/// ```
+#[macro_export]
+macro_rules! awrite_or {
+    ($aw:expr, $or:expr, $($tt:tt)*) => {
+        match $crate::awrite!($aw, $($tt)*) {
+            ::core::result::Result::Ok(n) => n,
+            ::core::result::Result::Err(_) => $or,
+        }
+    };
+}
+
+/// Like [`awrite!`] but flushes the part that fit even if formatting fails.
+///
+/// The error is still returned. See [`AwriteBuf::awrite_fmt_partial`].
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite_partial, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+/// let field = "oversized field";
+/// assert!(awrite_partial!(buf, "id={field}").is_err());
+/// assert_eq!(buf.sink_ref(), b"id=overs");
+/// assert!(buf.is_empty());
+///
+/// assert_eq!(awrite_partial!(buf, "ok"), Ok(2));
+/// # })
+/// ```
+#[macro_export]
+macro_rules! awrite_partial {
+    ($aw:expr, $($tt:tt)*) => {
+        $aw.awrite_fmt_partial(format_args!($($tt)*)).await
+    };
+}
+
+/// Format into the scratch without flushing.
+///
+/// Yields `Result<(), WriteFmtError<Error<E>>>`. Use [`aflush!`] to flush
+/// several buffered fragments in one sink transaction.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use core::convert::Infallible;
+/// use embedded_io::{ErrorType, Write};
+/// use awrite::{aflush, awrite_buffered, awriteln_buffered, AwriteBuf};
+///
+/// #[derive(Default)]
+/// struct Counting(Vec<u8>, usize);
+///
+/// impl ErrorType for Counting {
+///     type Error = Infallible;
+/// }
+///
+/// impl embedded_io_async::Write for Counting {
+///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Infallible> {
+///         self.0.extend_from_slice(buf);
+///         self.1 += 1;
+///         Ok(buf.len())
+///     }
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 32], Counting::default());
+/// awrite_buffered!(buf, "a={} ", 1).unwrap();
+/// awrite_buffered!(buf, "b={} ", 2).unwrap();
+/// awriteln_buffered!(&mut buf, "c={}", 3).unwrap();
+/// aflush!(buf).unwrap();
+///
+/// let sink = buf.into_sink();
+/// assert_eq!(sink.0, b"a=1 b=2 c=3\n");
+/// assert_eq!(sink.1, 1);
+/// # })
+/// ```
+#[macro_export]
+macro_rules! awrite_buffered {
+    ($aw:expr, $($tt:tt)*) => {
+        ::core::write!($aw, $($tt)*)
+    };
+}
+
+/// Like [`awrite_buffered!`] with a trailing newline.
+///
+/// ```
+/// use embedded_io::Write;
+///
+/// let mut buf = awrite::AwriteBuf::new([0u8; 8], Vec::<u8>::new());
+/// awrite::awriteln_buffered!(buf).unwrap();
+/// awrite::awriteln_buffered!(buf, "{}", 1).unwrap();
+/// assert_eq!(buf.written(), b"\n1\n");
+/// ```
+#[macro_export]
+macro_rules! awriteln_buffered {
+    ($aw:expr $(,)?) => {
+        $crate::awrite_buffered!($aw, "\n")
+    };
+    ($aw:expr, $($tt:tt)*) => {
+        ::core::writeln!($aw, $($tt)*)
+    };
+}
+
+/// Flush the scratch to the sink.
+///
+/// Yields `Result<(), Error<E>>`, the flush result as it is. This deliberately
+/// differs from [`awrite!`], which yields `WriteFmtError<Error<E>>` because its
+/// formatting can fail. Without formatting there is nothing to map:
+/// `?` into `Error<E>` works for both, see `From<WriteFmtError<Error<E>>>`.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use awrite::{aflush, AwriteBuf};
+///
+/// let mut sink = Vec::<u8>::new();
+/// let mut buf = AwriteBuf::new([0u8; 16], &mut sink);
+///
+/// write!(buf, "a").unwrap();
+/// aflush!(buf).unwrap();
+/// write!(buf, "b").unwrap();
+/// aflush!(&mut buf).unwrap();
+///
+/// assert_eq!(sink, b"ab");
+/// # })
+/// ```
+#[macro_export]
+macro_rules! aflush {
+    ($aw:expr $(,)?) => {
+        embedded_io_async::Write::flush(&mut $aw).await
+    };
+}
+
+/// Write a string without formatting and flush, see [`AwriteBuf::write_str_fast`].
+///
+/// Yields `Result<(), Error<E>>`.
+#[macro_export]
+macro_rules! astr {
+    ($aw:expr, $s:expr $(,)?) => {
+        $aw.write_str_fast($s).await
+    };
+}
+
+/// Write the `Display` of each argument in turn and flush once.
+///
+/// Yields the number of bytes flushed like [`awrite!`].
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{concat_write, AwriteBuf};
+///
+/// let (level, module, msg) = ("INFO", module_path!(), 42);
+/// let mut buf = AwriteBuf::new([0u8; 64], Vec::<u8>::new());
+/// let n = concat_write!(buf, level, " ", module, ": ", msg).unwrap();
+/// assert_eq!(buf.sink_ref().len(), n);
+/// assert!(buf.sink_ref().ends_with(b": 42"));
+/// assert!(buf.is_empty());
+/// # })
+/// ```
+#[macro_export]
+macro_rules! concat_write {
+    ($aw:expr, $($arg:expr),+ $(,)?) => {{
+        let mut ret = Ok(());
+        $(
+            if ret.is_ok() {
+                ret = embedded_io::Write::write_fmt(&mut $aw, format_args!("{}", $arg));
+            }
+        )+
+        match ret {
+            Ok(()) => {
+                let len = $aw.len();
+                match embedded_io_async::Write::flush(&mut $aw).await {
+                    Ok(()) => Ok(len - $aw.len()),
+                    Err(e) => Err(embedded_io::WriteFmtError::Other(e)),
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }};
 }
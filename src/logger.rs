@@ -0,0 +1,114 @@
+use core::{cell::RefCell, future::Future, pin::pin, task::Poll};
+use embassy_sync::blocking_mutex::{raw::RawMutex, Mutex};
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// [`log::Log`] backend formatting records into a shared [`AwriteBuf`]
+///
+/// Each record is formatted as `[LEVEL target] message\n` while holding the
+/// blocking mutex `M`. As `log()` can't await, it then polls the flush once
+/// without a waker: a ready sink receives the record right away, the rest of a
+/// busy sink's share stays buffered. [`AwriteBuf::flush_cancel_safe`] is used, so
+/// a flush cut short never duplicates output, and the line prefix and
+/// indentation of the buffer do not apply. Buffered bytes go out with the
+/// next record or with [`LogBackend::flush`] from a task.
+/// Records that don't fit into the scratch are dropped.
+///
+/// ```
+/// use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+/// use log::Log;
+/// use awrite::{AwriteBuf, LogBackend};
+///
+/// let logger = LogBackend::<CriticalSectionRawMutex, _, _>::new(AwriteBuf::new(
+///     [0u8; 64],
+///     Vec::<u8>::new(),
+/// ));
+/// logger.log(
+///     &log::Record::builder()
+///         .level(log::Level::Warn)
+///         .target("app::net")
+///         .args(format_args!("link down: {}", 3))
+///         .build(),
+/// );
+/// let buf = logger.into_inner();
+/// assert_eq!(buf.sink_ref(), b"[WARN app::net] link down: 3\n");
+/// ```
+///
+/// Install it with `log::set_logger()` from a `static`, e.g. through `static_cell`.
+pub struct LogBackend<M: RawMutex, T, U> {
+    buf: Mutex<M, RefCell<AwriteBuf<T, U>>>,
+}
+
+impl<M: RawMutex, T, U> LogBackend<M, T, U> {
+    pub const fn new(buf: AwriteBuf<T, U>) -> Self {
+        Self {
+            buf: Mutex::new(RefCell::new(buf)),
+        }
+    }
+
+    /// Access the buffer while holding the lock.
+    pub fn lock<R>(&self, f: impl FnOnce(&mut AwriteBuf<T, U>) -> R) -> R {
+        self.buf.lock(|buf| f(&mut buf.borrow_mut()))
+    }
+
+    pub fn into_inner(self) -> AwriteBuf<T, U> {
+        self.buf.into_inner().into_inner()
+    }
+}
+
+impl<M: RawMutex, T: Scratch, U: embedded_io_async::Write> LogBackend<M, T, U> {
+    fn poll_flush(&self, cx: &mut core::task::Context<'_>) -> Poll<Result<(), Error<U::Error>>> {
+        self.lock(|buf| pin!(buf.flush_cancel_safe()).poll(cx))
+    }
+
+    /// Flush what `log()` could not, e.g. from a low-priority task.
+    ///
+    /// The lock is only held while polling, not across awaits.
+    pub async fn flush(&self) -> Result<(), Error<U::Error>> {
+        core::future::poll_fn(|cx| self.poll_flush(cx)).await
+    }
+}
+
+impl<M, T, U> log::Log for LogBackend<M, T, U>
+where
+    M: RawMutex + Send + Sync,
+    T: Scratch + Send,
+    U: embedded_io_async::Write + Send,
+{
+    // Filtering is left to `log::set_max_level()`
+    fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        self.lock(|buf| {
+            let start = buf.len();
+            let ret = embedded_io::Write::write_fmt(
+                buf,
+                format_args!(
+                    "[{} {}] {}\n",
+                    record.level(),
+                    record.target(),
+                    record.args()
+                ),
+            );
+            if ret.is_err() {
+                buf.set_position(start);
+            }
+        });
+        self.poll_flush_once();
+    }
+
+    fn flush(&self) {
+        self.poll_flush_once();
+    }
+}
+
+impl<M: RawMutex, T: Scratch, U: embedded_io_async::Write> LogBackend<M, T, U> {
+    // A single attempt: errors can't be reported from `log::Log` and a pending
+    // sink can't be waited for, in both cases the bytes stay buffered
+    fn poll_flush_once(&self) {
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let _ = self.poll_flush(&mut cx);
+    }
+}
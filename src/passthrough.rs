@@ -0,0 +1,88 @@
+use core::future::Future;
+use embedded_io::{ErrorType, WriteFmtError};
+
+use crate::Error;
+
+/// Unbuffered [`crate::awrite!`] target writing straight to the async sink
+///
+/// Each `awrite!` formats into an `N` byte fragment (64 by default) that lives
+/// only until the record reaches the sink, then writes and flushes it. Nothing is
+/// retained between records. Records longer than `N` fail with
+/// `Error::Sync(SliceWriteError::Full)` and nothing is written.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, awriteln, Passthrough};
+///
+/// let mut out = Passthrough::<_, 16>::new(Vec::<u8>::new());
+/// assert_eq!(awrite!(out, "t={}", 1), Ok(3));
+/// assert_eq!(out.sink_ref(), b"t=1");
+/// assert_eq!(awriteln!(out, " ok"), Ok(4));
+/// assert_eq!(out.sink_ref(), b"t=1 ok\n");
+/// assert!(awrite!(out, "{:20}", 0).is_err());
+/// assert_eq!(out.sink_ref(), b"t=1 ok\n");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct Passthrough<U, const N: usize = 64> {
+    sink: U,
+}
+
+impl<U, const N: usize> Passthrough<U, N> {
+    pub fn new(sink: U) -> Self {
+        Self { sink }
+    }
+
+    pub fn sink_ref(&self) -> &U {
+        &self.sink
+    }
+
+    pub fn sink_mut(&mut self) -> &mut U {
+        &mut self.sink
+    }
+
+    pub fn into_sink(self) -> U {
+        self.sink
+    }
+}
+
+impl<U: embedded_io_async::Write, const N: usize> Passthrough<U, N> {
+    /// Format a record and write it to the sink, yielding its length.
+    ///
+    /// See [`crate::AwriteBuf::awrite_fmt`].
+    pub fn awrite_fmt(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+    ) -> impl Future<Output = Result<usize, WriteFmtError<Error<U::Error>>>> + '_ {
+        let mut frag = [0; N];
+        let mut rest = &mut frag[..];
+        let ret = embedded_io::Write::write_fmt(&mut rest, args).map_err(|e| match e {
+            WriteFmtError::Other(e) => WriteFmtError::Other(Error::Sync(e)),
+            WriteFmtError::FmtError => WriteFmtError::FmtError,
+        });
+        let len = N - rest.len();
+        async move {
+            ret?;
+            self.sink
+                .write_all(&frag[..len])
+                .await
+                .map_err(Error::Async)?;
+            self.sink.flush().await.map_err(Error::Async)?;
+            Ok(len)
+        }
+    }
+}
+
+impl<U: ErrorType, const N: usize> ErrorType for Passthrough<U, N> {
+    type Error = Error<U::Error>;
+}
+
+impl<U: embedded_io_async::Write, const N: usize> embedded_io_async::Write for Passthrough<U, N> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.sink.write(buf).await.map_err(Error::Async)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.sink.flush().await.map_err(Error::Async)
+    }
+}
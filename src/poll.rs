@@ -0,0 +1,120 @@
+use core::task::{Context, Poll};
+use embedded_io::ErrorType;
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Poll based sink for executor-agnostic flushing, see [`AwriteBuf::poll_flush`]
+pub trait PollWrite: ErrorType {
+    /// Attempt to write some bytes from `buf`, returning how many were written.
+    ///
+    /// Must not return `Ready(Ok(0))` for a non-empty `buf`.
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, Self::Error>>;
+}
+
+impl<T: PollWrite + ?Sized> PollWrite for &mut T {
+    fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, Self::Error>> {
+        T::poll_write(self, cx, buf)
+    }
+}
+
+impl<T: Scratch, U: PollWrite> AwriteBuf<T, U> {
+    /// Drive flushing the scratch to the sink without `async`.
+    ///
+    /// Progress made before a `Pending` is kept, the next poll resumes with
+    /// the remaining bytes.
+    ///
+    /// Sticky errors, retries and UTF-8 boundaries apply like in the async flush.
+    /// The flush hook is called with the bytes written by the poll that completes
+    /// the flush. The line prefix and indentation do not apply, and [`PollWrite`]
+    /// has no sink flush.
+    ///
+    /// ```
+    /// use core::task::{Context, Poll, Waker};
+    /// use embedded_io::Write;
+    /// use awrite::{AwriteBuf, PollWrite};
+    ///
+    /// #[derive(Default)]
+    /// struct Once(bool, Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Once {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl PollWrite for Once {
+    ///     fn poll_write(&mut self, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, Self::Error>> {
+    ///         if !core::mem::replace(&mut self.0, true) {
+    ///             return Poll::Pending;
+    ///         }
+    ///         self.1.extend_from_slice(buf);
+    ///         Poll::Ready(Ok(buf.len()))
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let mut buf = AwriteBuf::new([0u8; 16], Once::default());
+    /// write!(buf, "polled").unwrap();
+    ///
+    /// assert!(buf.poll_flush(&mut cx).is_pending());
+    /// assert_eq!(buf.len(), 6);
+    /// assert_eq!(buf.poll_flush(&mut cx), Poll::Ready(Ok(())));
+    /// assert!(buf.is_empty());
+    /// assert_eq!(buf.sink_ref().1, b"polled");
+    /// ```
+    ///
+    /// A failing sink latches with [`AwriteBuf::set_error_sticky`]:
+    ///
+    /// ```
+    /// use core::task::{Context, Poll, Waker};
+    /// use embedded_io::{ErrorKind, Write};
+    /// use awrite::{AwriteBuf, Error, PollWrite};
+    ///
+    /// struct Broken(usize);
+    ///
+    /// impl embedded_io::ErrorType for Broken {
+    ///     type Error = ErrorKind;
+    /// }
+    ///
+    /// impl PollWrite for Broken {
+    ///     fn poll_write(&mut self, _cx: &mut Context<'_>, _buf: &[u8]) -> Poll<Result<usize, Self::Error>> {
+    ///         self.0 += 1;
+    ///         Poll::Ready(Err(ErrorKind::BrokenPipe))
+    ///     }
+    /// }
+    ///
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let mut buf = AwriteBuf::new([0u8; 16], Broken(0));
+    /// buf.set_error_sticky(true);
+    /// write!(buf, "polled").unwrap();
+    /// assert_eq!(buf.poll_flush(&mut cx), Poll::Ready(Err(Error::Async(ErrorKind::BrokenPipe))));
+    /// assert_eq!(buf.poll_flush(&mut cx), Poll::Ready(Err(Error::Latched(ErrorKind::BrokenPipe))));
+    /// assert_eq!(buf.sink_ref().0, 1);
+    /// assert_eq!(buf.written(), b"polled");
+    /// ```
+    pub fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error<U::Error>>> {
+        if let Err(e) = self.check_latched() {
+            return Poll::Ready(Err(e));
+        }
+        let n = self.flushable();
+        let mut sent = 0;
+        let mut retries = self.config.retries;
+        while sent < n {
+            match self.sink.poll_write(cx, &self.buf.as_ref()[..n - sent]) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Ok(0)) => panic!("poll_write() returned Ok(0)"),
+                Poll::Ready(Ok(written)) => {
+                    self.consume(written);
+                    sent += written;
+                }
+                Poll::Ready(Err(e))
+                    if retries > 0
+                        && embedded_io::Error::kind(&e) == embedded_io::ErrorKind::Interrupted =>
+                {
+                    retries -= 1;
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(self.latch(Err(Error::Async(e)))),
+            }
+        }
+        self.flushed(sent);
+        Poll::Ready(Ok(()))
+    }
+}
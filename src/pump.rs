@@ -0,0 +1,73 @@
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Error of [`AwriteBuf::pump`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PumpError<R, E> {
+    /// The source failed
+    Read(R),
+    /// Buffering or flushing failed
+    Write(Error<E>),
+}
+
+impl<R: embedded_io::Error, E: embedded_io::Error> embedded_io::Error for PumpError<R, E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            Self::Read(e) => e.kind(),
+            Self::Write(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> AwriteBuf<T, U> {
+    /// Relay up to `max` bytes from `src` to the sink, reading straight into the scratch.
+    ///
+    /// Stops early at the end of `src`, flushes and returns the number of bytes relayed.
+    /// CRLF translation does not apply.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::AwriteBuf;
+    ///
+    /// let data: Vec<u8> = (0..100).collect();
+    /// let mut buf = AwriteBuf::new([0u8; 16], Vec::<u8>::new());
+    ///
+    /// let mut src = &data[..];
+    /// assert_eq!(buf.pump(&mut src, 40).await, Ok(40));
+    /// assert_eq!(buf.pump(&mut src, 1000).await, Ok(60));
+    /// assert_eq!(buf.sink_ref(), &data);
+    /// # })
+    /// ```
+    pub async fn pump<R: embedded_io_async::Read>(
+        &mut self,
+        src: &mut R,
+        max: usize,
+    ) -> Result<usize, PumpError<R::Error, U::Error>> {
+        let mut relayed = 0;
+        while relayed < max {
+            if self.remaining() == 0 {
+                self.force_flush().await.map_err(PumpError::Write)?;
+            }
+            self.buf.grow(self.pos + 1);
+            let n = (max - relayed).min(self.remaining());
+            if n == 0 {
+                return Err(PumpError::Write(Error::Sync(
+                    embedded_io::SliceWriteError::Full,
+                )));
+            }
+            self.buf.grow(self.pos + n);
+            let read = src
+                .read(&mut self.buf.as_mut()[self.pos..self.pos + n])
+                .await
+                .map_err(PumpError::Read)?;
+            if read == 0 {
+                break;
+            }
+            self.pos += read;
+            relayed += read;
+        }
+        embedded_io_async::Write::flush(self)
+            .await
+            .map_err(PumpError::Write)?;
+        Ok(relayed)
+    }
+}
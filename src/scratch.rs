@@ -0,0 +1,267 @@
+/// Storage for the [`crate::AwriteBuf`] scratch
+///
+/// The buffer writes into `as_mut()` and flushes from `as_ref()`.
+/// Growable storage makes room in [`Scratch::grow`], fixed size storage
+/// keeps the default no-op.
+pub trait Scratch: AsRef<[u8]> + AsMut<[u8]> {
+    /// Try to make at least `len` bytes available.
+    fn grow(&mut self, len: usize) {
+        let _ = len;
+    }
+
+    /// Number of bytes the scratch can hold, growing up to this is cheap.
+    fn capacity(&self) -> usize {
+        self.as_ref().len()
+    }
+
+    /// Number of bytes [`Scratch::grow`] can make available, `usize::MAX` if unbounded.
+    fn max_capacity(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl Scratch for [u8] {}
+
+impl<const N: usize> Scratch for [u8; N] {}
+
+impl<S: Scratch + ?Sized> Scratch for &mut S {
+    fn grow(&mut self, len: usize) {
+        (**self).grow(len)
+    }
+
+    fn capacity(&self) -> usize {
+        (**self).capacity()
+    }
+
+    fn max_capacity(&self) -> usize {
+        (**self).max_capacity()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Scratch for alloc::boxed::Box<[u8]> {}
+
+/// Grows instead of failing with `SliceWriteError::Full`
+///
+/// The capacity is the allocated capacity of the `Vec`, the growth is unbounded:
+/// the buffer is never [full](crate::AwriteBuf::is_full).
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::AwriteBuf;
+///
+/// let msg: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+/// let mut sink = Vec::<u8>::new();
+/// let mut buf = AwriteBuf::new(Vec::new(), &mut sink);
+///
+/// embedded_io::Write::write_all(&mut buf, &msg).unwrap();
+/// assert_eq!(buf.len(), 1000);
+/// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+///
+/// assert_eq!(sink, msg);
+///
+/// let mut buf = AwriteBuf::new(Vec::new(), Vec::<u8>::new());
+/// buf.set_high_water(50);
+/// assert!(!buf.is_full());
+/// assert!(!buf.needs_flush());
+/// assert_eq!(buf.try_write_backpressured(b"abc"), core::task::Poll::Ready(Ok(3)));
+/// # })
+/// ```
+#[cfg(feature = "alloc")]
+impl Scratch for alloc::vec::Vec<u8> {
+    fn grow(&mut self, len: usize) {
+        if len > self.len() {
+            self.resize(len, 0);
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    fn max_capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// `Vec` scratch that grows up to `max` bytes, then fails with `SliceWriteError::Full`
+///
+/// Growth uses `try_reserve()`, an allocation failure is reported as a full scratch as well.
+/// The capacity is `max`.
+///
+/// ```
+/// use embedded_io::Write;
+/// use awrite::{AwriteBuf, BoundedVecScratch, Error};
+///
+/// let mut buf = AwriteBuf::new(BoundedVecScratch::new(8), Vec::<u8>::new());
+/// buf.write_all(b"12345678").unwrap();
+/// assert!(buf.is_full());
+/// assert_eq!(
+///     buf.write(b"9"),
+///     Err(Error::Sync(embedded_io::SliceWriteError::Full))
+/// );
+/// let (scratch, _) = buf.into_parts();
+/// assert_eq!(scratch.into_inner(), b"12345678");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct BoundedVecScratch {
+    vec: alloc::vec::Vec<u8>,
+    max: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl BoundedVecScratch {
+    /// Empty scratch, nothing is allocated until the first write.
+    pub fn new(max: usize) -> Self {
+        Self {
+            vec: alloc::vec::Vec::new(),
+            max,
+        }
+    }
+
+    pub fn into_inner(self) -> alloc::vec::Vec<u8> {
+        self.vec
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for BoundedVecScratch {
+    fn as_ref(&self) -> &[u8] {
+        &self.vec
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsMut<[u8]> for BoundedVecScratch {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.vec
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Scratch for BoundedVecScratch {
+    fn grow(&mut self, len: usize) {
+        let len = len.min(self.max);
+        if len > self.vec.len() && self.vec.try_reserve(len - self.vec.len()).is_ok() {
+            self.vec.resize(len, 0);
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.max
+    }
+}
+
+/// Uses the full capacity `N`, not only the current length
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::AwriteBuf;
+///
+/// let mut sink = Vec::<u8>::new();
+/// let mut buf = AwriteBuf::new(heapless::Vec::<u8, 64>::new(), &mut sink);
+/// assert_eq!(buf.capacity(), 64);
+///
+/// embedded_io::Write::write_all(&mut buf, &[0x55; 64]).unwrap();
+/// assert!(embedded_io::Write::write(&mut buf, &[0]).is_err());
+/// embedded_io_async::Write::flush(&mut buf).await.unwrap();
+///
+/// assert_eq!(sink, [0x55; 64]);
+/// # })
+/// ```
+#[cfg(feature = "heapless")]
+impl<const N: usize> Scratch for heapless::Vec<u8, N> {
+    fn grow(&mut self, len: usize) {
+        if len > self.len() {
+            self.resize(len.min(N), 0).ok();
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+}
+
+/// Fixed size scratch that is not zeroed up front
+///
+/// Only the prefix that has been made available by [`Scratch::grow`] is
+/// initialized (once, on first use) and exposed, the rest is never read.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use core::mem::MaybeUninit;
+/// use awrite::{awrite, AwriteBuf, Scratch, Uninit};
+///
+/// let scratch = Uninit::new([MaybeUninit::<u8>::uninit(); 4096]);
+/// assert!(scratch.as_ref().is_empty());
+/// assert_eq!(scratch.capacity(), 4096);
+///
+/// let mut buf = AwriteBuf::new(scratch, Vec::<u8>::new());
+/// awrite!(buf, "{}", 1234).unwrap();
+/// awrite!(buf, "{}", 5).unwrap();
+/// assert_eq!(buf.sink_ref(), b"12345");
+/// # })
+/// ```
+#[derive(Clone, Copy)]
+pub struct Uninit<const N: usize> {
+    buf: [core::mem::MaybeUninit<u8>; N],
+    init: usize,
+}
+
+impl<const N: usize> Uninit<N> {
+    pub fn new(buf: [core::mem::MaybeUninit<u8>; N]) -> Self {
+        Self { buf, init: 0 }
+    }
+}
+
+impl<const N: usize> Default for Uninit<N> {
+    fn default() -> Self {
+        Self::new([core::mem::MaybeUninit::uninit(); N])
+    }
+}
+
+impl<const N: usize> From<[core::mem::MaybeUninit<u8>; N]> for Uninit<N> {
+    fn from(buf: [core::mem::MaybeUninit<u8>; N]) -> Self {
+        Self::new(buf)
+    }
+}
+
+impl<const N: usize> core::fmt::Debug for Uninit<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Uninit")
+            .field("buf", &self.as_ref())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Uninit<N> {
+    fn as_ref(&self) -> &[u8] {
+        // SAFETY: `buf[..init]` has been initialized in `grow()` and `init <= N`.
+        // `MaybeUninit<u8>` has the same layout as `u8`.
+        unsafe { core::slice::from_raw_parts(self.buf.as_ptr().cast(), self.init) }
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for Uninit<N> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        // SAFETY: See `as_ref()`. Writes through the slice keep it initialized.
+        unsafe { core::slice::from_raw_parts_mut(self.buf.as_mut_ptr().cast(), self.init) }
+    }
+}
+
+impl<const N: usize> Scratch for Uninit<N> {
+    fn grow(&mut self, len: usize) {
+        let len = len.min(N);
+        if len > self.init {
+            for b in &mut self.buf[self.init..len] {
+                b.write(0);
+            }
+            self.init = len;
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+}
@@ -0,0 +1,78 @@
+//! Sink adapters to be used as the [`crate::AwriteBuf`] sink
+
+mod base64;
+pub use base64::*;
+mod capacity;
+pub use capacity::*;
+mod cobs;
+pub use cobs::*;
+mod counting;
+pub use counting::*;
+mod crc;
+pub use crc::*;
+mod discard;
+pub use discard::*;
+mod dma;
+pub use dma::*;
+mod escape;
+pub use escape::*;
+mod hex;
+pub use hex::*;
+mod level;
+pub use level::*;
+mod limit;
+pub use limit::*;
+mod mirror;
+pub use mirror::*;
+mod paginate;
+pub use paginate::*;
+mod prefix;
+pub use prefix::*;
+mod reserve;
+pub use reserve::*;
+mod session;
+pub use session::*;
+mod slip;
+pub use slip::*;
+mod tee;
+pub use tee::*;
+mod timestamp;
+pub use timestamp::*;
+
+#[cfg(feature = "alloc")]
+mod framed;
+#[cfg(feature = "alloc")]
+pub use framed::*;
+#[cfg(feature = "alloc")]
+mod loopback;
+#[cfg(feature = "alloc")]
+pub use loopback::*;
+
+#[cfg(feature = "futures")]
+mod futures;
+#[cfg(feature = "futures")]
+pub use futures::*;
+
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use tokio::*;
+
+#[cfg(feature = "embedded-hal-async")]
+mod rate;
+#[cfg(feature = "embedded-hal-async")]
+pub use rate::*;
+
+#[cfg(feature = "embedded-hal-nb")]
+mod nb;
+#[cfg(feature = "embedded-hal-nb")]
+pub use nb::*;
+
+#[cfg(feature = "embassy-sync")]
+mod channel;
+#[cfg(feature = "embassy-sync")]
+pub use channel::*;
+#[cfg(feature = "embassy-sync")]
+mod shared;
+#[cfg(feature = "embassy-sync")]
+pub use shared::*;
@@ -0,0 +1,96 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Base64 encode everything written (standard alphabet, padded)
+///
+/// Input is grouped into three byte blocks across `write()` calls. `flush()`
+/// emits the final partial group with `=` padding.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::sink::Base64Sink;
+///
+/// for (input, output) in [
+///     (&b"M"[..], &b"TQ=="[..]),
+///     (b"Ma", b"TWE="),
+///     (b"Man", b"TWFu"),
+///     (b"hello", b"aGVsbG8="),
+/// ] {
+///     let mut b64 = Base64Sink::new(Vec::new());
+///     for b in input {
+///         b64.write_all(&[*b]).await.unwrap();
+///     }
+///     b64.flush().await.unwrap();
+///     assert_eq!(b64.into_inner(), output);
+/// }
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct Base64Sink<W> {
+    inner: W,
+    group: [u8; 3],
+    len: usize,
+}
+
+impl<W> Base64Sink<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            group: [0; 3],
+            len: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn encode(&self, out: &mut [u8]) {
+        let [a, b, c] = self.group;
+        out[0] = ALPHABET[(a >> 2) as usize];
+        out[1] = ALPHABET[((a & 0x3) << 4 | b >> 4) as usize];
+        out[2] = ALPHABET[((b & 0xf) << 2 | c >> 6) as usize];
+        out[3] = ALPHABET[(c & 0x3f) as usize];
+    }
+}
+
+impl<W: ErrorType> ErrorType for Base64Sink<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for Base64Sink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let mut chunk = [0u8; 48];
+        let mut n = 0;
+        for b in buf {
+            self.group[self.len] = *b;
+            self.len += 1;
+            if self.len == 3 {
+                self.encode(&mut chunk[n..n + 4]);
+                self.len = 0;
+                n += 4;
+                if n == chunk.len() {
+                    self.inner.write_all(&chunk).await?;
+                    n = 0;
+                }
+            }
+        }
+        self.inner.write_all(&chunk[..n]).await?;
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.len > 0 {
+            self.group[self.len..].fill(0);
+            let mut chunk = [0u8; 4];
+            self.encode(&mut chunk);
+            chunk[self.len + 1..].fill(b'=');
+            self.len = 0;
+            self.inner.write_all(&chunk).await?;
+        }
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,66 @@
+use crate::AwriteBuf;
+
+/// Sinks that can tell how many bytes they accept right now without waiting
+///
+/// E.g. the free space in the ring buffer of a UART driver. The default is
+/// `None`, for unknown. A blanket implementation for every sink would conflict
+/// with the specific ones on stable Rust, so implement it for your sink as needed.
+pub trait CapacitySink {
+    fn free(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<S: CapacitySink + ?Sized> CapacitySink for &mut S {
+    fn free(&self) -> Option<usize> {
+        (**self).free()
+    }
+}
+
+impl<T, U: CapacitySink> AwriteBuf<T, U> {
+    /// Bytes the sink accepts right now, see [`CapacitySink`]
+    ///
+    /// Compare with [`AwriteBuf::len`] to flush only if it won't stall.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{aflush, sink::CapacitySink, AwriteBuf};
+    ///
+    /// // Ring buffer with room for 4 bytes
+    /// #[derive(Default)]
+    /// struct Ring(Vec<u8>);
+    ///
+    /// impl CapacitySink for Ring {
+    ///     fn free(&self) -> Option<usize> {
+    ///         Some(4 - self.0.len())
+    ///     }
+    /// }
+    ///
+    /// impl embedded_io::ErrorType for Ring {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Ring {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         let n = buf.len().min(4 - self.0.len());
+    ///         self.0.extend_from_slice(&buf[..n]);
+    ///         Ok(n)
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Ring::default());
+    /// write!(buf, "abc").unwrap();
+    /// assert_eq!(buf.sink_free(), Some(4));
+    /// if buf.sink_free().is_some_and(|free| free >= buf.len()) {
+    ///     aflush!(buf).unwrap();
+    /// }
+    /// assert_eq!(buf.sink_free(), Some(1));
+    /// write!(buf, "de").unwrap();
+    /// assert!(buf.sink_free() < Some(buf.len()));
+    /// # })
+    /// ```
+    pub fn sink_free(&self) -> Option<usize> {
+        self.sink_ref().free()
+    }
+}
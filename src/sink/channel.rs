@@ -0,0 +1,63 @@
+use core::convert::Infallible;
+use embassy_sync::{blocking_mutex::raw::RawMutex, channel::Channel};
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Hand bytes to another task through an `embassy_sync` channel
+///
+/// `write()` waits for room for the first byte and then queues as many as fit
+/// without waiting. Bytes are queued in order, so the receiving task, e.g. the one
+/// driving the UART, sees the stream as written.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embassy_sync::{blocking_mutex::raw::NoopRawMutex, channel::Channel};
+/// use awrite::{awrite, sink::ChannelSink, AwriteBuf};
+///
+/// let chan = Channel::<NoopRawMutex, u8, 4>::new();
+/// let mut buf = AwriteBuf::new([0u8; 16], ChannelSink::new(&chan));
+///
+/// let mut out = Vec::new();
+/// let producer = async { awrite!(buf, "byte order {}", 42) };
+/// let (ret, ()) = futures::join!(producer, async {
+///     while out.len() < 13 {
+///         out.push(chan.receive().await);
+///     }
+/// });
+/// assert_eq!(ret, Ok(13));
+/// assert_eq!(out, b"byte order 42");
+/// # })
+/// ```
+pub struct ChannelSink<'a, M: RawMutex, const N: usize> {
+    chan: &'a Channel<M, u8, N>,
+}
+
+impl<'a, M: RawMutex, const N: usize> ChannelSink<'a, M, N> {
+    pub fn new(chan: &'a Channel<M, u8, N>) -> Self {
+        Self { chan }
+    }
+}
+
+impl<M: RawMutex, const N: usize> Clone for ChannelSink<'_, M, N> {
+    fn clone(&self) -> Self {
+        Self { chan: self.chan }
+    }
+}
+
+impl<M: RawMutex, const N: usize> ErrorType for ChannelSink<'_, M, N> {
+    type Error = Infallible;
+}
+
+impl<M: RawMutex, const N: usize> Write for ChannelSink<'_, M, N> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let Some((&first, rest)) = buf.split_first() else {
+            return Ok(0);
+        };
+        self.chan.send(first).await;
+        let queued = rest
+            .iter()
+            .take_while(|&&b| self.chan.try_send(b).is_ok())
+            .count();
+        Ok(1 + queued)
+    }
+}
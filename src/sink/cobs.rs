@@ -0,0 +1,92 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// COBS encode everything written and terminate the frame with `0x00` on `flush()`
+///
+/// Runs of up to 254 non-zero bytes are buffered internally.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::sink::CobsSink;
+///
+/// let mut cobs = CobsSink::new(Vec::new());
+/// cobs.write_all(&[0x11, 0x22, 0x00]).await.unwrap();
+/// cobs.write_all(&[0x33]).await.unwrap();
+/// cobs.flush().await.unwrap();
+/// cobs.write_all(&[0x00]).await.unwrap();
+/// cobs.flush().await.unwrap();
+/// assert_eq!(cobs.into_inner(), [0x03, 0x11, 0x22, 0x02, 0x33, 0x00, 0x01, 0x01, 0x00]);
+///
+/// let mut cobs = CobsSink::new(Vec::new());
+/// let run: Vec<u8> = (1..=254).collect();
+/// cobs.write_all(&run).await.unwrap();
+/// cobs.flush().await.unwrap();
+/// let out = cobs.into_inner();
+/// assert_eq!((out[0], &out[1..255], out[255]), (0xff, &run[..], 0x00));
+/// # })
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct CobsSink<W> {
+    inner: W,
+    // Code byte followed by up to 254 non-zero data bytes
+    block: [u8; 255],
+    len: usize,
+    // The last block was a full run that implies no zero
+    full: bool,
+}
+
+impl<W> CobsSink<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            block: [0; 255],
+            len: 0,
+            full: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> CobsSink<W> {
+    async fn emit(&mut self) -> Result<(), W::Error> {
+        self.block[0] = self.len as u8 + 1;
+        self.inner.write_all(&self.block[..self.len + 1]).await?;
+        self.full = self.len == 254;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl<W: ErrorType> ErrorType for CobsSink<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for CobsSink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        for &b in buf {
+            if b == 0 {
+                self.emit().await?;
+            } else {
+                self.len += 1;
+                self.block[self.len] = b;
+                if self.len == 254 {
+                    self.emit().await?;
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.len > 0 || !self.full {
+            self.emit().await?;
+        }
+        self.full = false;
+        self.inner.write_all(&[0]).await?;
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,74 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Count the bytes passed to the inner sink, in total and per `flush()`
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, sink::{Counting, CrcSink}, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], CrcSink::new(Counting::new(Vec::new())));
+/// awrite!(buf, "abc").unwrap();
+/// awrite!(buf, "{}", 1234567).unwrap();
+///
+/// // Including the two CRC bytes appended per flush
+/// let counting = buf.into_sink().into_inner();
+/// assert_eq!((counting.last_flush_len(), counting.total()), (9, 14));
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct Counting<W> {
+    inner: W,
+    current: u64,
+    last_flush_len: u64,
+    total: u64,
+}
+
+impl<W> Counting<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            current: 0,
+            last_flush_len: 0,
+            total: 0,
+        }
+    }
+
+    /// Bytes written between the last two `flush()` calls
+    pub fn last_flush_len(&self) -> u64 {
+        self.last_flush_len
+    }
+
+    /// Bytes written since construction or [`Self::reset_counts`]
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn reset_counts(&mut self) {
+        self.current = 0;
+        self.last_flush_len = 0;
+        self.total = 0;
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType> ErrorType for Counting<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for Counting<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written = self.inner.write(buf).await?;
+        self.current += written as u64;
+        self.total += written as u64;
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.last_flush_len = core::mem::take(&mut self.current);
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,95 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Append a CRC-16 over the bytes written since the last flush on every `flush()`
+///
+/// A flush without bytes written since the last one appends nothing.
+/// Defaults to CRC-16/CCITT-FALSE (polynomial `0x1021`, init `0xffff`, not reflected).
+/// The checksum is appended big-endian.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::sink::CrcSink;
+///
+/// let mut crc = CrcSink::new(Vec::new());
+/// crc.write_all(b"1234").await.unwrap();
+/// crc.write_all(b"56789").await.unwrap();
+/// assert_eq!(crc.checksum(), 0x29b1);
+/// crc.flush().await.unwrap();
+/// crc.write_all(b"123456789").await.unwrap();
+/// crc.flush().await.unwrap();
+/// crc.flush().await.unwrap();
+///
+/// assert_eq!(crc.into_inner(), b"123456789\x29\xb1123456789\x29\xb1");
+/// # })
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct CrcSink<W> {
+    inner: W,
+    poly: u16,
+    init: u16,
+    crc: u16,
+    // Bytes were written since the last flush
+    pending: bool,
+}
+
+impl<W> CrcSink<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_params(inner, 0x1021, 0xffff)
+    }
+
+    pub fn with_params(inner: W, poly: u16, init: u16) -> Self {
+        Self {
+            inner,
+            poly,
+            init,
+            crc: init,
+            pending: false,
+        }
+    }
+
+    /// CRC over the bytes written since the last flush
+    pub fn checksum(&self) -> u16 {
+        self.crc
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn update(&mut self, buf: &[u8]) {
+        for b in buf {
+            self.crc ^= (*b as u16) << 8;
+            for _ in 0..8 {
+                self.crc = if self.crc & 0x8000 != 0 {
+                    (self.crc << 1) ^ self.poly
+                } else {
+                    self.crc << 1
+                };
+            }
+        }
+    }
+}
+
+impl<W: ErrorType> ErrorType for CrcSink<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for CrcSink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written = self.inner.write(buf).await?;
+        self.update(&buf[..written]);
+        self.pending |= written > 0;
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.pending {
+            self.inner.write_all(&self.crc.to_be_bytes()).await?;
+            self.crc = self.init;
+            self.pending = false;
+        }
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,33 @@
+use core::convert::Infallible;
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Accept and drop everything, like `/dev/null`
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, sink::DiscardSink, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], DiscardSink);
+/// assert_eq!(awrite!(buf, "{}", 1234), Ok(4));
+/// assert!(buf.is_empty());
+/// assert_eq!(buf.total_flushed(), 4);
+/// assert_eq!(buf.into_sink(), DiscardSink);
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DiscardSink;
+
+impl ErrorType for DiscardSink {
+    type Error = Infallible;
+}
+
+impl Write for DiscardSink {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
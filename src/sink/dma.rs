@@ -0,0 +1,107 @@
+use embedded_io::ErrorType;
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Sinks that transmit a whole slice in one transfer, e.g. by DMA
+///
+/// The slice stays borrowed, and its address stable, until the returned
+/// future completes. See [`AwriteBuf::flush_dma`].
+#[allow(async_fn_in_trait)]
+pub trait DmaWrite: ErrorType {
+    /// Transmit all of `buf`.
+    async fn write_dma(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl<S: DmaWrite + ?Sized> DmaWrite for &mut S {
+    async fn write_dma(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_dma(buf).await
+    }
+}
+
+impl<T: Scratch, U: DmaWrite> AwriteBuf<T, U> {
+    /// Hand the buffered bytes to the sink in one transfer.
+    ///
+    /// The bytes are only dropped from the scratch after the transfer completes.
+    /// If it fails, or the future is dropped, they stay buffered.
+    /// Sticky errors, UTF-8 boundaries and the flush hook apply like in the async
+    /// flush. The line prefix, indentation and retries do not: the transfer is
+    /// the buffered slice as it is.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::Write;
+    /// use awrite::{sink::DmaWrite, AwriteBuf};
+    ///
+    /// #[derive(Default)]
+    /// struct Dma(Vec<Vec<u8>>);
+    ///
+    /// impl embedded_io::ErrorType for Dma {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl DmaWrite for Dma {
+    ///     async fn write_dma(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+    ///         self.0.push(buf.to_vec());
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Dma::default());
+    /// write!(buf, "frame {}", 1).unwrap();
+    /// buf.flush_dma().await.unwrap();
+    /// assert!(buf.is_empty());
+    ///
+    /// // Nothing buffered, no transfer
+    /// buf.flush_dma().await.unwrap();
+    /// assert_eq!(buf.sink_ref().0, [b"frame 1"]);
+    /// assert_eq!(buf.total_flushed(), 7);
+    /// # })
+    /// ```
+    ///
+    /// A failed transfer latches with [`AwriteBuf::set_error_sticky`]:
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io::{ErrorKind, Write};
+    /// use awrite::{sink::DmaWrite, AwriteBuf, Error};
+    ///
+    /// #[derive(Default)]
+    /// struct Broken(usize);
+    ///
+    /// impl embedded_io::ErrorType for Broken {
+    ///     type Error = ErrorKind;
+    /// }
+    ///
+    /// impl DmaWrite for Broken {
+    ///     async fn write_dma(&mut self, _buf: &[u8]) -> Result<(), Self::Error> {
+    ///         self.0 += 1;
+    ///         Err(ErrorKind::BrokenPipe)
+    ///     }
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Broken::default());
+    /// buf.set_error_sticky(true);
+    /// write!(buf, "frame").unwrap();
+    /// assert_eq!(buf.flush_dma().await, Err(Error::Async(ErrorKind::BrokenPipe)));
+    /// assert_eq!(buf.flush_dma().await, Err(Error::Latched(ErrorKind::BrokenPipe)));
+    /// assert_eq!(buf.sink_ref().0, 1);
+    /// assert_eq!(buf.written(), b"frame");
+    /// # })
+    /// ```
+    pub async fn flush_dma(&mut self) -> Result<(), Error<U::Error>> {
+        self.check_latched()?;
+        let n = self.flushable();
+        if n == 0 {
+            return Ok(());
+        }
+        let ret = self
+            .sink
+            .write_dma(&self.buf.as_ref()[..n])
+            .await
+            .map_err(Error::Async);
+        self.latch(ret)?;
+        self.consume(n);
+        self.flushed(n);
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Escape control characters as `\xNN` for terminal-safe output
+///
+/// Printable ASCII, bytes above 0x7f, `\n` and `\t` pass through unchanged.
+/// Every other byte in 0x00-0x1f and 0x7f is replaced by a lowercase `\xNN` escape.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::sink::EscapeSink;
+///
+/// let mut esc = EscapeSink::new(Vec::new());
+/// esc.write_all(b"red: \x1b[31m\tok\n").await.unwrap();
+/// esc.write_all(b"\x7f").await.unwrap();
+/// esc.write_all(b"\x00").await.unwrap();
+/// assert_eq!(esc.into_inner(), b"red: \\x1b[31m\tok\n\\x7f\\x00");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct EscapeSink<W> {
+    inner: W,
+}
+
+impl<W> EscapeSink<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType> ErrorType for EscapeSink<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for EscapeSink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let mut chunk = [0u8; 48];
+        let mut n = 0;
+        for &b in buf {
+            if n + 4 > chunk.len() {
+                self.inner.write_all(&chunk[..n]).await?;
+                n = 0;
+            }
+            if (b < 0x20 && b != b'\n' && b != b'\t') || b == 0x7f {
+                chunk[n..n + 4].copy_from_slice(&[
+                    b'\\',
+                    b'x',
+                    HEX[(b >> 4) as usize],
+                    HEX[(b & 0xf) as usize],
+                ]);
+                n += 4;
+            } else {
+                chunk[n] = b;
+                n += 1;
+            }
+        }
+        self.inner.write_all(&chunk[..n]).await?;
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,90 @@
+use alloc::vec::Vec;
+use embedded_io::{ErrorKind, ErrorType};
+use embedded_io_async::Write;
+
+/// Prefix each flushed record with its length as a little-endian `u16`
+///
+/// The record is collected until `flush()`, which emits length and payload.
+/// A flush without data emits nothing. A write that would grow the record beyond
+/// `u16::MAX` bytes fails with [`FramedError::TooLong`] and is not collected.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::{awrite, sink::{Framed, FramedError}, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], Framed::new(Vec::new()));
+/// awrite!(buf, "abc").unwrap();
+/// awrite!(buf, "{}", 1234).unwrap();
+///
+/// let mut framed = buf.into_sink();
+/// assert_eq!(framed.write(&[0; 0x1_0000]).await, Err(FramedError::TooLong));
+/// assert_eq!(framed.into_inner(), b"\x03\x00abc\x04\x001234");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct Framed<W> {
+    inner: W,
+    record: Vec<u8>,
+}
+
+impl<W> Framed<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            record: Vec::new(),
+        }
+    }
+
+    /// Take the inner sink, dropping a record not yet flushed.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Error of a [`Framed`] sink
+#[derive(Debug, Clone, PartialEq)]
+pub enum FramedError<E> {
+    /// The record would not fit the `u16` length prefix
+    TooLong,
+    Inner(E),
+}
+
+impl<E: embedded_io::Error> embedded_io::Error for FramedError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::TooLong => ErrorKind::InvalidInput,
+            Self::Inner(e) => e.kind(),
+        }
+    }
+}
+
+impl<W: ErrorType> ErrorType for Framed<W> {
+    type Error = FramedError<W::Error>;
+}
+
+impl<W: Write> Write for Framed<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if self.record.len() + buf.len() > u16::MAX as usize {
+            return Err(FramedError::TooLong);
+        }
+        self.record.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        if !self.record.is_empty() {
+            let len = self.record.len() as u16;
+            self.inner
+                .write_all(&len.to_le_bytes())
+                .await
+                .map_err(FramedError::Inner)?;
+            self.inner
+                .write_all(&self.record)
+                .await
+                .map_err(FramedError::Inner)?;
+            self.record.clear();
+        }
+        self.inner.flush().await.map_err(FramedError::Inner)
+    }
+}
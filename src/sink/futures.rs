@@ -0,0 +1,46 @@
+use core::future::poll_fn;
+use core::pin::Pin;
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+use futures_io::AsyncWrite;
+
+/// Use a `futures::io::AsyncWrite` as the sink
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use futures::io::Cursor;
+/// use awrite::{awrite, sink::FuturesSink, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], FuturesSink::new(Cursor::new(Vec::new())));
+/// awrite!(buf, "{}", 42).unwrap();
+///
+/// assert_eq!(buf.into_sink().into_inner().into_inner(), b"42");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct FuturesSink<W>(W);
+
+impl<W> FuturesSink<W> {
+    pub fn new(inner: W) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+impl<W> ErrorType for FuturesSink<W> {
+    type Error = std::io::Error;
+}
+
+impl<W: AsyncWrite + Unpin> Write for FuturesSink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        poll_fn(|cx| Pin::new(&mut self.0).poll_write(cx, buf)).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        poll_fn(|cx| Pin::new(&mut self.0).poll_flush(cx)).await
+    }
+}
@@ -0,0 +1,76 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Hex dump everything written as lowercase digit pairs
+///
+/// Bytes are separated by `sep` (if any). There is no separator before the
+/// first byte after construction or `flush()`.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::sink::HexWrite;
+///
+/// let mut hex = HexWrite::new(Vec::new(), Some(b' '));
+/// hex.write_all(b"He").await.unwrap();
+/// hex.write_all(&[0x0a, 0xff]).await.unwrap();
+/// assert_eq!(hex.into_inner(), b"48 65 0a ff");
+///
+/// let mut hex = HexWrite::new(Vec::new(), None);
+/// hex.write_all(&[1, 2, 3, 4]).await.unwrap();
+/// assert_eq!(hex.into_inner(), b"01020304");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct HexWrite<W> {
+    inner: W,
+    sep: Option<u8>,
+    first: bool,
+}
+
+impl<W> HexWrite<W> {
+    pub fn new(inner: W, sep: Option<u8>) -> Self {
+        Self {
+            inner,
+            sep,
+            first: true,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType> ErrorType for HexWrite<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for HexWrite<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        let mut chunk = [0u8; 48];
+        let mut n = 0;
+        for b in buf {
+            if n + 3 > chunk.len() {
+                self.inner.write_all(&chunk[..n]).await?;
+                n = 0;
+            }
+            if let Some(sep) = self.sep.filter(|_| !self.first) {
+                chunk[n] = sep;
+                n += 1;
+            }
+            self.first = false;
+            chunk[n] = HEX[(b >> 4) as usize];
+            chunk[n + 1] = HEX[(b & 0xf) as usize];
+            n += 2;
+        }
+        self.inner.write_all(&chunk[..n]).await?;
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.first = true;
+        self.inner.flush().await
+    }
+}
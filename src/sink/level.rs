@@ -0,0 +1,151 @@
+use core::ops::{Deref, DerefMut};
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+use crate::AwriteBuf;
+
+/// Drop records below a runtime level threshold
+///
+/// [`LevelSink::set_next_level`] tags everything written after it, until the next call.
+/// Writes tagged below [`LevelSink::level`] are reported as written and discarded.
+/// Bytes still buffered in an [`crate::AwriteBuf`] get the level current at flush time,
+/// so flush a record before tagging the next one. [`crate::awrite!`] does that already.
+/// Before the first tag, writes pass.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, sink::LevelSink, AwriteBuf};
+///
+/// const DEBUG: u8 = 1;
+/// const ERROR: u8 = 3;
+///
+/// let mut buf = AwriteBuf::new([0u8; 32], LevelSink::new(Vec::new(), ERROR));
+/// buf.sink_mut().set_next_level(DEBUG);
+/// awrite!(buf, "debug: {}\n", 1).unwrap();
+/// buf.sink_mut().set_next_level(ERROR);
+/// awrite!(buf, "error: {}\n", 2).unwrap();
+/// assert_eq!(buf.into_sink().into_inner(), b"error: 2\n");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct LevelSink<W, L = u8> {
+    inner: W,
+    level: L,
+    next: L,
+}
+
+impl<W, L: PartialOrd + Clone> LevelSink<W, L> {
+    pub fn new(inner: W, level: L) -> Self {
+        Self {
+            inner,
+            next: level.clone(),
+            level,
+        }
+    }
+
+    /// Current threshold.
+    pub fn level(&self) -> &L {
+        &self.level
+    }
+
+    /// Change the threshold.
+    pub fn set_level(&mut self, level: L) {
+        self.level = level;
+    }
+
+    /// Tag the following writes with `level`.
+    pub fn set_next_level(&mut self, level: L) {
+        self.next = level;
+    }
+
+    /// Whether writes are currently forwarded.
+    pub fn enabled(&self) -> bool {
+        self.next >= self.level
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType, L> ErrorType for LevelSink<W, L> {
+    type Error = W::Error;
+}
+
+impl<W: Write, L: PartialOrd + Clone> Write for LevelSink<W, L> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if self.enabled() {
+            self.inner.write(buf).await
+        } else {
+            Ok(buf.len())
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
+
+/// Guard restoring the [`LevelSink`] threshold on drop, see [`AwriteBuf::scoped_level`]
+///
+/// Dereferences to the buffer to write through it.
+#[derive(Debug)]
+pub struct ScopedLevel<'a, T, W, L: PartialOrd + Clone> {
+    buf: &'a mut AwriteBuf<T, LevelSink<W, L>>,
+    prev: Option<L>,
+}
+
+impl<T, W, L: PartialOrd + Clone> AwriteBuf<T, LevelSink<W, L>> {
+    /// Change the threshold of the [`LevelSink`] until the guard is dropped.
+    ///
+    /// The threshold applies when bytes reach the sink, so flush within the scope.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use awrite::{awrite, sink::LevelSink, AwriteBuf};
+    ///
+    /// const DEBUG: u8 = 1;
+    /// const ERROR: u8 = 3;
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], LevelSink::new(Vec::new(), ERROR));
+    /// buf.sink_mut().set_next_level(DEBUG);
+    /// {
+    ///     let mut verbose = buf.scoped_level(DEBUG);
+    ///     awrite!(verbose, "shown ").unwrap();
+    /// }
+    /// assert_eq!(*buf.sink_ref().level(), ERROR);
+    /// awrite!(buf, "hidden").unwrap();
+    /// assert_eq!(buf.into_sink().into_inner(), b"shown ");
+    /// # })
+    /// ```
+    pub fn scoped_level(&mut self, level: L) -> ScopedLevel<'_, T, W, L> {
+        let prev = self.sink_ref().level().clone();
+        self.sink_mut().set_level(level);
+        ScopedLevel {
+            buf: self,
+            prev: Some(prev),
+        }
+    }
+}
+
+impl<T, W, L: PartialOrd + Clone> Deref for ScopedLevel<'_, T, W, L> {
+    type Target = AwriteBuf<T, LevelSink<W, L>>;
+
+    fn deref(&self) -> &Self::Target {
+        self.buf
+    }
+}
+
+impl<T, W, L: PartialOrd + Clone> DerefMut for ScopedLevel<'_, T, W, L> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.buf
+    }
+}
+
+impl<T, W, L: PartialOrd + Clone> Drop for ScopedLevel<'_, T, W, L> {
+    fn drop(&mut self) {
+        if let Some(prev) = self.prev.take() {
+            self.buf.sink_mut().set_level(prev);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use embedded_io::{ErrorKind, ErrorType};
+use embedded_io_async::Write;
+
+/// Forward at most `max` bytes in total, then fail with [`LimitError::Exceeded`]
+///
+/// A write straddling the limit is cut short at the limit.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::sink::{Limit, LimitError};
+///
+/// let mut lim = Limit::new(Vec::new(), 5);
+/// assert_eq!(lim.write(b"abc").await, Ok(3));
+/// assert_eq!(lim.write(b"defg").await, Ok(2));
+/// assert_eq!(lim.remaining(), 0);
+/// assert_eq!(lim.write(b"h").await, Err(LimitError::Exceeded));
+/// assert_eq!(lim.into_inner(), b"abcde");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct Limit<W> {
+    inner: W,
+    max: u64,
+    written: u64,
+}
+
+impl<W> Limit<W> {
+    pub fn new(inner: W, max: u64) -> Self {
+        Self {
+            inner,
+            max,
+            written: 0,
+        }
+    }
+
+    /// Bytes that can still be written before the limit.
+    pub fn remaining(&self) -> u64 {
+        self.max - self.written
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Error of a [`Limit`] sink
+#[derive(Debug, Clone, PartialEq)]
+pub enum LimitError<E> {
+    /// The byte budget is used up
+    Exceeded,
+    Inner(E),
+}
+
+impl<E: embedded_io::Error> embedded_io::Error for LimitError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Exceeded => ErrorKind::WriteZero,
+            Self::Inner(e) => e.kind(),
+        }
+    }
+}
+
+impl<W: ErrorType> ErrorType for Limit<W> {
+    type Error = LimitError<W::Error>;
+}
+
+impl<W: Write> Write for Limit<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let n = buf
+            .len()
+            .min(self.remaining().try_into().unwrap_or(usize::MAX));
+        if n == 0 {
+            return Err(LimitError::Exceeded);
+        }
+        let written = self
+            .inner
+            .write(&buf[..n])
+            .await
+            .map_err(LimitError::Inner)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await.map_err(LimitError::Inner)
+    }
+}
@@ -0,0 +1,76 @@
+use alloc::collections::VecDeque;
+use core::convert::Infallible;
+use embedded_io::ErrorType;
+use embedded_io_async::{Read, Write};
+
+/// In-memory byte queue sink that can be read back, e.g. for loopback tests
+///
+/// Reads are partial: they return what is queued, up to the length of the
+/// buffer. The queue can't fill while it is borrowed for reading, so reading
+/// an empty queue returns `Ok(0)` instead of waiting, like the end of a stream.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Read;
+/// use awrite::{awrite, sink::LoopbackSink, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], LoopbackSink::new());
+/// awrite!(buf, "ping {}", 1).unwrap();
+///
+/// let mut rx = [0u8; 4];
+/// let lo = buf.sink_mut();
+/// assert_eq!(lo.read(&mut rx).await, Ok(4));
+/// assert_eq!(&rx, b"ping");
+/// assert_eq!(lo.read(&mut rx).await, Ok(2));
+/// assert_eq!(&rx[..2], b" 1");
+/// assert_eq!(lo.read(&mut rx).await, Ok(0));
+///
+/// // Usable as a generic reader
+/// async fn expect<R: Read>(r: &mut R, want: &[u8]) {
+///     let mut got = [0u8; 8];
+///     r.read_exact(&mut got[..want.len()]).await.unwrap();
+///     assert_eq!(&got[..want.len()], want);
+/// }
+///
+/// awrite!(buf, "pong").unwrap();
+/// expect(buf.sink_mut(), b"po").await;
+/// expect(buf.sink_mut(), b"ng").await;
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LoopbackSink {
+    queue: VecDeque<u8>,
+}
+
+impl LoopbackSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of bytes written but not read yet
+    pub fn available(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+impl ErrorType for LoopbackSink {
+    type Error = Infallible;
+}
+
+/// Read the oldest unread bytes
+impl Read for LoopbackSink {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let n = buf.len().min(self.queue.len());
+        for (dst, src) in buf.iter_mut().zip(self.queue.drain(..n)) {
+            *dst = src;
+        }
+        Ok(n)
+    }
+}
+
+impl Write for LoopbackSink {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.queue.extend(buf);
+        Ok(buf.len())
+    }
+}
@@ -0,0 +1,84 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Forward to the inner sink and retain the last `N` bytes in a ring
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, sink::MirrorSink, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], MirrorSink::<_, 8>::new(Vec::new()));
+/// awrite!(buf, "boot ").unwrap();
+/// awrite!(buf, "panic at {}", 42).unwrap();
+///
+/// let mirror = buf.into_sink();
+/// let mut tail = [0u8; 16];
+/// let n = mirror.snapshot(&mut tail);
+/// assert_eq!(&tail[..n], b"ic at 42");
+/// assert_eq!(mirror.snapshot(&mut tail[..2]), 2);
+/// assert_eq!(&tail[..2], b"42");
+/// assert_eq!(mirror.into_inner(), b"boot panic at 42");
+/// # })
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct MirrorSink<W, const N: usize> {
+    inner: W,
+    ring: [u8; N],
+    head: usize,
+    len: usize,
+}
+
+impl<W, const N: usize> MirrorSink<W, N> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            ring: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Copy the most recent retained bytes, oldest first, into `out`.
+    ///
+    /// Returns the number of bytes copied, at most `N` and `out.len()`.
+    pub fn snapshot(&self, out: &mut [u8]) -> usize {
+        let n = self.len.min(out.len());
+        let start = (self.head + N - n) % N.max(1);
+        let (a, b) = self.ring.split_at(start);
+        let first = b.len().min(n);
+        out[..first].copy_from_slice(&b[..first]);
+        out[first..n].copy_from_slice(&a[..n - first]);
+        n
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn retain(&mut self, mut buf: &[u8]) {
+        if buf.len() > N {
+            buf = &buf[buf.len() - N..];
+        }
+        let first = buf.len().min(N - self.head);
+        self.ring[self.head..self.head + first].copy_from_slice(&buf[..first]);
+        self.ring[..buf.len() - first].copy_from_slice(&buf[first..]);
+        self.head = (self.head + buf.len()) % N.max(1);
+        self.len = (self.len + buf.len()).min(N);
+    }
+}
+
+impl<W: ErrorType, const N: usize> ErrorType for MirrorSink<W, N> {
+    type Error = W::Error;
+}
+
+impl<W: Write, const N: usize> Write for MirrorSink<W, N> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let written = self.inner.write(buf).await?;
+        self.retain(&buf[..written]);
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,117 @@
+use core::task::Poll;
+use embedded_hal_nb::{nb, serial};
+use embedded_io::{ErrorKind, ErrorType};
+use embedded_io_async::Write;
+
+/// Use a blocking `embedded_hal_nb::serial::Write` as the sink
+///
+/// `WouldBlock` yields to the executor before retrying.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_hal_nb::{nb, serial};
+/// use awrite::{awrite, sink::NbSink, AwriteBuf};
+///
+/// #[derive(Default)]
+/// struct Uart {
+///     busy: usize,
+///     tx: Vec<u8>,
+/// }
+///
+/// impl serial::ErrorType for Uart {
+///     type Error = serial::ErrorKind;
+/// }
+///
+/// impl serial::Write for Uart {
+///     fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+///         if self.busy < 2 {
+///             self.busy += 1;
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         self.tx.push(word);
+///         Ok(())
+///     }
+///
+///     fn flush(&mut self) -> nb::Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], NbSink::new(Uart::default()));
+/// awrite!(buf, "nb").unwrap();
+/// let uart = buf.into_sink().into_inner();
+/// assert_eq!((uart.busy, &uart.tx[..]), (2, &b"nb"[..]));
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct NbSink<W>(W);
+
+impl<W> NbSink<W> {
+    pub fn new(inner: W) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+/// Error of the [`NbSink`] serial port
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbError<E>(pub E);
+
+impl<E: serial::Error> embedded_io::Error for NbError<E> {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+impl<W: serial::ErrorType> ErrorType for NbSink<W> {
+    type Error = NbError<W::Error>;
+}
+
+async fn yield_now() {
+    let mut yielded = false;
+    core::future::poll_fn(|cx| {
+        if core::mem::replace(&mut yielded, true) {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+async fn retry<E>(mut f: impl FnMut() -> nb::Result<(), E>) -> Result<(), NbError<E>> {
+    loop {
+        match f() {
+            Ok(()) => return Ok(()),
+            Err(nb::Error::WouldBlock) => yield_now().await,
+            Err(nb::Error::Other(e)) => return Err(NbError(e)),
+        }
+    }
+}
+
+impl<W: serial::Write> Write for NbSink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let Some((first, rest)) = buf.split_first() else {
+            return Ok(0);
+        };
+        retry(|| self.0.write(*first)).await?;
+        // Take more only while the port doesn't block
+        let mut written = 1;
+        for word in rest {
+            match self.0.write(*word) {
+                Ok(()) => written += 1,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => return Err(NbError(e)),
+            }
+        }
+        Ok(written)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        retry(|| self.0.flush()).await
+    }
+}
@@ -0,0 +1,67 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Emit a header every `page_size` bytes
+///
+/// The header goes right before the first byte of each page after the first.
+/// It is not counted towards the page.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, sink::PaginateSink, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], PaginateSink::new(Vec::new(), b"--\n", 4));
+/// awrite!(buf, "0123456789").unwrap();
+/// awrite!(buf, "ab").unwrap();
+/// assert_eq!(buf.into_sink().into_inner(), b"0123--\n4567--\n89ab");
+/// # })
+/// ```
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub struct PaginateSink<W> {
+    inner: W,
+    header: &'static [u8],
+    page_size: usize,
+    offset: usize,
+}
+
+impl<W> PaginateSink<W> {
+    /// Panics if `page_size` is zero.
+    pub fn new(inner: W, header: &'static [u8], page_size: usize) -> Self {
+        assert!(page_size > 0, "zero page size");
+        Self {
+            inner,
+            header,
+            page_size,
+            offset: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType> ErrorType for PaginateSink<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for PaginateSink<W> {
+    async fn write(&mut self, mut buf: &[u8]) -> Result<usize, Self::Error> {
+        let len = buf.len();
+        while !buf.is_empty() {
+            if self.offset == self.page_size {
+                self.inner.write_all(self.header).await?;
+                self.offset = 0;
+            }
+            let n = buf.len().min(self.page_size - self.offset);
+            self.inner.write_all(&buf[..n]).await?;
+            self.offset += n;
+            buf = &buf[n..];
+        }
+        Ok(len)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,60 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Emit a fixed header before the first byte written after construction or `flush()`
+///
+/// The prefix is written once per flush, however many `write()` calls the flush
+/// consists of. A flush without data emits nothing.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use awrite::{awrite, sink::Prefix, AwriteBuf};
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], Prefix::new(Vec::new(), b"\xaa\x55"));
+/// awrite!(buf, "one").unwrap();
+/// awrite!(buf, "two").unwrap();
+/// assert_eq!(buf.into_sink().into_inner(), b"\xaa\x55one\xaa\x55two");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct Prefix<W> {
+    inner: W,
+    prefix: &'static [u8],
+    pending: bool,
+}
+
+impl<W> Prefix<W> {
+    pub fn new(inner: W, prefix: &'static [u8]) -> Self {
+        Self {
+            inner,
+            prefix,
+            pending: true,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType> ErrorType for Prefix<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for Prefix<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pending {
+            self.inner.write_all(self.prefix).await?;
+            self.pending = false;
+        }
+        self.inner.write(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.pending = true;
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,101 @@
+use embedded_hal_async::delay::DelayNs;
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+use super::Clock;
+
+/// Start a new flush no sooner than `interval_ms` after the previous one
+///
+/// The first write after a `flush()` waits out the remainder of the interval.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use core::cell::Cell;
+/// use awrite::{awrite, sink::{Clock, RateLimit}, AwriteBuf};
+///
+/// #[derive(Default)]
+/// struct Time(Cell<u64>, Cell<u32>);
+///
+/// impl Clock for Time {
+///     fn now_millis(&self) -> u64 {
+///         self.0.get()
+///     }
+/// }
+///
+/// impl embedded_hal_async::delay::DelayNs for &Time {
+///     async fn delay_ns(&mut self, ns: u32) {
+///         self.0.set(self.0.get() + (ns / 1_000_000) as u64);
+///         self.1.set(self.1.get() + 1);
+///     }
+/// }
+///
+/// let time = Time::default();
+/// let sink = RateLimit::new(Vec::new(), &time, &time, 100);
+/// let mut buf = AwriteBuf::new([0u8; 16], sink);
+/// awrite!(buf, "a").unwrap();
+/// time.0.set(30);
+/// awrite!(buf, "b").unwrap();
+/// assert_eq!((time.0.get(), time.1.get()), (100, 1));
+///
+/// time.0.set(250);
+/// awrite!(buf, "c").unwrap();
+/// assert_eq!(time.1.get(), 1);
+/// assert_eq!(buf.into_sink().into_inner(), b"abc");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct RateLimit<W, C, D> {
+    inner: W,
+    clock: C,
+    delay: D,
+    interval_ms: u64,
+    last: Option<u64>,
+    armed: bool,
+}
+
+impl<W, C, D> RateLimit<W, C, D> {
+    pub fn new(inner: W, clock: C, delay: D, interval_ms: u64) -> Self {
+        Self {
+            inner,
+            clock,
+            delay,
+            interval_ms,
+            last: None,
+            armed: true,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType, C, D> ErrorType for RateLimit<W, C, D> {
+    type Error = W::Error;
+}
+
+impl<W: Write, C: Clock, D: DelayNs> Write for RateLimit<W, C, D> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if core::mem::take(&mut self.armed) {
+            if let Some(last) = self.last {
+                let wait = (last + self.interval_ms).saturating_sub(self.clock.now_millis());
+                if wait > 0 {
+                    self.delay
+                        .delay_ms(wait.try_into().unwrap_or(u32::MAX))
+                        .await;
+                }
+            }
+        }
+        self.inner.write(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await?;
+        self.last = Some(self.clock.now_millis());
+        self.armed = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Sinks that can pre-allocate room for upcoming writes
+///
+/// The default is a no-op. A blanket implementation for every sink would conflict
+/// with the specific ones on stable Rust, so implement it for your sink as needed.
+///
+/// For the same reason the async `flush()` of [`AwriteBuf`] does not reserve:
+/// it is available for every sink and can't require this trait. Flush with
+/// [`AwriteBuf::flush_reserve`] to reserve first.
+pub trait SinkReserve {
+    fn reserve(&mut self, additional: usize) {
+        let _ = additional;
+    }
+}
+
+impl<S: SinkReserve + ?Sized> SinkReserve for &mut S {
+    fn reserve(&mut self, additional: usize) {
+        (**self).reserve(additional)
+    }
+}
+
+/// Reserve before flushing to avoid repeated reallocation
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use awrite::AwriteBuf;
+///
+/// let mut buf = AwriteBuf::new([0u8; 256], Vec::<u8>::new());
+/// buf.write_all(&[0x55; 200]).unwrap();
+/// buf.flush_reserve().await.unwrap();
+///
+/// let sink = buf.into_sink();
+/// assert_eq!(sink.len(), 200);
+/// assert!(sink.capacity() >= 200);
+/// # })
+/// ```
+#[cfg(feature = "alloc")]
+impl SinkReserve for alloc::vec::Vec<u8> {
+    fn reserve(&mut self, additional: usize) {
+        alloc::vec::Vec::reserve(self, additional)
+    }
+}
+
+impl<T: Scratch, U: SinkReserve + embedded_io_async::Write> AwriteBuf<T, U> {
+    /// Let the sink reserve room for the buffered bytes, then flush.
+    ///
+    /// The reserving counterpart of `embedded_io_async::Write::flush()`, see [`SinkReserve`].
+    pub async fn flush_reserve(&mut self) -> Result<(), Error<U::Error>> {
+        let len = self.len();
+        self.sink_mut().reserve(len);
+        embedded_io_async::Write::flush(self).await
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Sinks that frame output with an explicit `open`/`close` handshake
+///
+/// See [`AwriteBuf::begin_session`] and [`AwriteBuf::end_session`].
+#[allow(async_fn_in_trait)]
+pub trait Session: embedded_io_async::Write {
+    /// Prepare for the first write of a session.
+    async fn open(&mut self) -> Result<(), Self::Error>;
+
+    /// Finish a session after the last flush.
+    async fn close(&mut self) -> Result<(), Self::Error>;
+}
+
+impl<S: Session + ?Sized> Session for &mut S {
+    async fn open(&mut self) -> Result<(), Self::Error> {
+        (**self).open().await
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        (**self).close().await
+    }
+}
+
+/// Sessions spanning several flushes
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use awrite::{awrite, sink::Session, AwriteBuf};
+///
+/// #[derive(Default)]
+/// struct Log(Vec<String>);
+///
+/// impl embedded_io::ErrorType for Log {
+///     type Error = core::convert::Infallible;
+/// }
+///
+/// impl embedded_io_async::Write for Log {
+///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+///         self.0.push(format!("write {}", core::str::from_utf8(buf).unwrap()));
+///         Ok(buf.len())
+///     }
+/// }
+///
+/// impl Session for Log {
+///     async fn open(&mut self) -> Result<(), Self::Error> {
+///         self.0.push("open".into());
+///         Ok(())
+///     }
+///
+///     async fn close(&mut self) -> Result<(), Self::Error> {
+///         self.0.push("close".into());
+///         Ok(())
+///     }
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], Log::default());
+/// buf.begin_session().await.unwrap();
+/// awrite!(buf, "a").unwrap();
+/// write!(buf, "b").unwrap();
+/// buf.end_session().await.unwrap();
+///
+/// assert_eq!(buf.sink_ref().0, ["open", "write a", "write b", "close"]);
+/// # })
+/// ```
+impl<T: Scratch, U: Session> AwriteBuf<T, U> {
+    /// Open a session on the sink.
+    pub async fn begin_session(&mut self) -> Result<(), Error<U::Error>> {
+        self.sink.open().await.map_err(Error::Async)
+    }
+
+    /// Flush pending data, then close the session on the sink.
+    pub async fn end_session(&mut self) -> Result<(), Error<U::Error>> {
+        self.flush_n(self.pos).await?;
+        self.sink.close().await.map_err(Error::Async)
+    }
+}
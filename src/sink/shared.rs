@@ -0,0 +1,147 @@
+use embassy_sync::{blocking_mutex::raw::RawMutex, mutex::Mutex};
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Share one sink between several buffers through an `embassy_sync` mutex
+///
+/// Every `write()` holds the lock until all of its bytes are written, every
+/// `flush()` locks on its own. A flush that takes several writes, e.g. with a
+/// line prefix or indentation, can interleave with those of other buffers.
+/// [`AwriteBuf::flush_locked`] holds the lock for the whole flush.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embassy_sync::{blocking_mutex::raw::NoopRawMutex, mutex::Mutex};
+/// use awrite::{awriteln, sink::SharedSink, AwriteBuf};
+///
+/// let uart = Mutex::<NoopRawMutex, _>::new(Vec::<u8>::new());
+/// let mut a = AwriteBuf::new([0u8; 32], SharedSink::new(&uart));
+/// let mut b = AwriteBuf::new([0u8; 32], SharedSink::new(&uart));
+///
+/// let (ra, rb) = futures::join!(
+///     async { awriteln!(a, "task a: {}", 1) },
+///     async { awriteln!(b, "task b: {}", 2) },
+/// );
+/// ra.unwrap();
+/// rb.unwrap();
+///
+/// assert_eq!(&uart.lock().await[..], b"task a: 1\ntask b: 2\n");
+/// # })
+/// ```
+pub struct SharedSink<'a, M: RawMutex, W> {
+    inner: &'a Mutex<M, W>,
+}
+
+impl<'a, M: RawMutex, W> SharedSink<'a, M, W> {
+    pub fn new(inner: &'a Mutex<M, W>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<M: RawMutex, W> Clone for SharedSink<'_, M, W> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner }
+    }
+}
+
+impl<M: RawMutex, W: ErrorType> ErrorType for SharedSink<'_, M, W> {
+    type Error = W::Error;
+}
+
+impl<M: RawMutex, W: Write> Write for SharedSink<'_, M, W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.inner.lock().await.write_all(buf).await?;
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.lock().await.flush().await
+    }
+}
+
+impl<T: Scratch, M: RawMutex, W: Write> AwriteBuf<T, SharedSink<'_, M, W>> {
+    /// Flush like [`AwriteBuf::force_flush`] while holding the lock of the shared sink.
+    ///
+    /// The lines of one flush stay together even when the sink is taken by
+    /// several writes.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::task::Poll;
+    /// use embassy_sync::{blocking_mutex::raw::NoopRawMutex, mutex::Mutex};
+    /// use embedded_io::Write;
+    /// use awrite::{sink::SharedSink, AwriteBuf};
+    ///
+    /// // Yields once per write, like a UART waiting for room
+    /// #[derive(Default)]
+    /// struct Uart(Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Uart {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Uart {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         let mut busy = true;
+    ///         core::future::poll_fn(|cx| {
+    ///             if core::mem::take(&mut busy) {
+    ///                 cx.waker().wake_by_ref();
+    ///                 Poll::Pending
+    ///             } else {
+    ///                 Poll::Ready(())
+    ///             }
+    ///         })
+    ///         .await;
+    ///         self.0.extend_from_slice(buf);
+    ///         Ok(buf.len())
+    ///     }
+    /// }
+    ///
+    /// let uart = Mutex::<NoopRawMutex, _>::new(Uart::default());
+    /// let mut a = AwriteBuf::new([0u8; 32], SharedSink::new(&uart));
+    /// let mut b = AwriteBuf::new([0u8; 32], SharedSink::new(&uart));
+    /// a.set_line_prefix(b"  ");
+    /// b.set_line_prefix(b"  ");
+    /// write!(a, "a:\n1\n2\n").unwrap();
+    /// write!(b, "b:\n3\n4\n").unwrap();
+    ///
+    /// let (ra, rb) = futures::join!(a.flush_locked(), b.flush_locked());
+    /// ra.unwrap();
+    /// rb.unwrap();
+    ///
+    /// assert_eq!(&uart.lock().await.0[..], b"a:\n  1\n  2\nb:\n  3\n  4\n");
+    /// # })
+    /// ```
+    pub async fn flush_locked(&mut self) -> Result<(), Error<W::Error>> {
+        let inner = self.sink.inner;
+        let mut guard = inner.lock().await;
+        let mut view = AwriteBuf {
+            buf: &mut self.buf,
+            sink: &mut *guard,
+            pos: self.pos,
+            total: self.total,
+            generation: self.generation,
+            since: self.since,
+            depth: self.depth,
+            latched: self.latched,
+            config: self.config,
+        };
+        // No await between the scratch moving and the state being copied back,
+        // a dropped flush leaves the buffer consistent
+        let n = view.flushable();
+        let ret = view.drain(n).await;
+        self.pos = view.pos;
+        self.total = view.total;
+        self.since = view.since;
+        self.latched = view.latched;
+        ret?;
+        if !self.config.skip_sink_flush {
+            let ret = guard.flush().await.map_err(Error::Async);
+            self.latch(ret)?;
+        }
+        self.flushed(n);
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+const END: u8 = 0xc0;
+const ESC: u8 = 0xdb;
+const ESC_END: u8 = 0xdc;
+const ESC_ESC: u8 = 0xdd;
+
+/// SLIP (RFC 1055) encode everything written and terminate the frame with `END` on `flush()`
+///
+/// A flush without bytes written since the last one sends no empty frame.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io_async::Write;
+/// use awrite::sink::SlipSink;
+///
+/// let mut slip = SlipSink::new(Vec::new());
+/// slip.write_all(&[0x01, 0xc0]).await.unwrap();
+/// slip.write_all(&[0xdb, 0x02]).await.unwrap();
+/// slip.flush().await.unwrap();
+/// slip.flush().await.unwrap();
+/// assert_eq!(slip.into_inner(), [0x01, 0xdb, 0xdc, 0xdb, 0xdd, 0x02, 0xc0]);
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct SlipSink<W> {
+    inner: W,
+    // Bytes were written since the last flush
+    pending: bool,
+}
+
+impl<W> SlipSink<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            pending: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType> ErrorType for SlipSink<W> {
+    type Error = W::Error;
+}
+
+impl<W: Write> Write for SlipSink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.pending |= !buf.is_empty();
+        let mut chunk = [0u8; 48];
+        let mut n = 0;
+        for &b in buf {
+            if n + 2 > chunk.len() {
+                self.inner.write_all(&chunk[..n]).await?;
+                n = 0;
+            }
+            match b {
+                END => {
+                    chunk[n..n + 2].copy_from_slice(&[ESC, ESC_END]);
+                    n += 2;
+                }
+                ESC => {
+                    chunk[n..n + 2].copy_from_slice(&[ESC, ESC_ESC]);
+                    n += 2;
+                }
+                b => {
+                    chunk[n] = b;
+                    n += 1;
+                }
+            }
+        }
+        self.inner.write_all(&chunk[..n]).await?;
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.pending {
+            self.inner.write_all(&[END]).await?;
+            self.pending = false;
+        }
+        self.inner.flush().await
+    }
+}
@@ -0,0 +1,177 @@
+use embedded_io::{ErrorKind, ErrorType};
+use embedded_io_async::Write;
+
+/// Write everything to two sinks
+///
+/// Both legs are always attempted. If either fails, the error of the first
+/// failing leg (`A` before `B`) is reported.
+///
+/// Each leg remembers how much of a failed write it has accepted. The next
+/// write is expected to start with the same bytes, as the resend by
+/// [`crate::AwriteBuf`] does, and each leg only gets what it is missing.
+/// A shorter write can't be a resend and starts over on both legs. If the
+/// bytes of a failed write are discarded instead, e.g. with
+/// [`crate::AwriteBuf::clear`], call [`Tee::reset`]: otherwise the next write
+/// loses its first bytes on the leg that had them.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use awrite::{awrite, sink::Tee, AwriteBuf};
+///
+/// let (mut a, mut b) = (Vec::<u8>::new(), Vec::<u8>::new());
+/// let mut buf = AwriteBuf::new([0u8; 16], Tee::new(&mut a, &mut b));
+/// awrite!(buf, "both").unwrap();
+/// drop(buf);
+///
+/// assert_eq!(a, b"both");
+/// assert_eq!(b, b"both");
+///
+/// // Accepts two bytes, then fails once
+/// #[derive(Default)]
+/// struct Flaky(Vec<u8>, bool);
+///
+/// impl embedded_io::ErrorType for Flaky {
+///     type Error = embedded_io::ErrorKind;
+/// }
+///
+/// impl embedded_io_async::Write for Flaky {
+///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+///         if self.0.len() == 2 && !core::mem::replace(&mut self.1, true) {
+///             return Err(embedded_io::ErrorKind::Other);
+///         }
+///         let n = buf.len().min(2);
+///         self.0.extend_from_slice(&buf[..n]);
+///         Ok(n)
+///     }
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 16], Tee::new(Flaky::default(), Vec::<u8>::new()));
+/// assert_eq!(
+///     awrite!(buf, "both"),
+///     Err(embedded_io::WriteFmtError::Other(awrite::Error::Async(
+///         awrite::sink::TeeError::A(embedded_io::ErrorKind::Other)
+///     )))
+/// );
+/// assert_eq!(buf.written(), b"both");
+/// awrite::aflush!(buf).unwrap();
+/// let (a, b) = buf.into_sink().into_inner();
+/// assert_eq!((&a.0[..], &b[..]), (&b"both"[..], &b"both"[..]));
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct Tee<A, B> {
+    a: A,
+    b: B,
+    // Bytes of the pending write each leg already has
+    a_sent: usize,
+    b_sent: usize,
+}
+
+impl<A, B> Tee<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            a_sent: 0,
+            b_sent: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.a, self.b)
+    }
+
+    /// Forget the progress of a failed write, the next write goes to both legs in full.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use embedded_io_async::Write;
+    /// use awrite::sink::{Tee, TeeError};
+    ///
+    /// // Accepts one byte, then fails once
+    /// #[derive(Default)]
+    /// struct Flaky(Vec<u8>, bool);
+    ///
+    /// impl embedded_io::ErrorType for Flaky {
+    ///     type Error = embedded_io::ErrorKind;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Flaky {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         if !self.0.is_empty() && !core::mem::replace(&mut self.1, true) {
+    ///             return Err(embedded_io::ErrorKind::Other);
+    ///         }
+    ///         let n = if self.0.is_empty() { 1 } else { buf.len() };
+    ///         self.0.extend_from_slice(&buf[..n]);
+    ///         Ok(n)
+    ///     }
+    /// }
+    ///
+    /// let mut tee = Tee::new(Flaky::default(), Vec::<u8>::new());
+    /// assert_eq!(tee.write(b"ab").await, Err(TeeError::A(embedded_io::ErrorKind::Other)));
+    /// // "ab" is given up
+    /// tee.reset();
+    /// tee.write_all(b"cd").await.unwrap();
+    /// let (a, b) = tee.into_inner();
+    /// assert_eq!((&a.0[..], &b[..]), (&b"acd"[..], &b"abcd"[..]));
+    /// # })
+    /// ```
+    pub fn reset(&mut self) {
+        self.a_sent = 0;
+        self.b_sent = 0;
+    }
+}
+
+/// Error of a [`Tee`] leg
+#[derive(Debug, Clone, PartialEq)]
+pub enum TeeError<A, B> {
+    A(A),
+    B(B),
+}
+
+impl<A: embedded_io::Error, B: embedded_io::Error> embedded_io::Error for TeeError<A, B> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::A(e) => e.kind(),
+            Self::B(e) => e.kind(),
+        }
+    }
+}
+
+impl<A: ErrorType, B: ErrorType> ErrorType for Tee<A, B> {
+    type Error = TeeError<A::Error, B::Error>;
+}
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if self.a_sent > buf.len() || self.b_sent > buf.len() {
+            self.reset();
+        }
+        let a = write_leg(&mut self.a, buf, &mut self.a_sent).await;
+        let b = write_leg(&mut self.b, buf, &mut self.b_sent).await;
+        a.map_err(TeeError::A)?;
+        b.map_err(TeeError::B)?;
+        self.a_sent -= buf.len();
+        self.b_sent -= buf.len();
+        Ok(buf.len())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        let a = self.a.flush().await;
+        let b = self.b.flush().await;
+        a.map_err(TeeError::A)?;
+        b.map_err(TeeError::B)
+    }
+}
+
+// Write what the leg does not have yet, counting what it accepts
+async fn write_leg<W: Write>(w: &mut W, buf: &[u8], sent: &mut usize) -> Result<(), W::Error> {
+    while *sent < buf.len() {
+        match w.write(&buf[*sent..]).await? {
+            0 => panic!("write() returned Ok(0)"),
+            n => *sent += n,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+
+/// Millisecond time source for [`TimestampSink`]
+pub trait Clock {
+    fn now_millis(&self) -> u64;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now_millis(&self) -> u64 {
+        (**self).now_millis()
+    }
+}
+
+/// Prefix every line with a decimal millisecond timestamp and a separator
+///
+/// The timestamp is taken when the first byte of a line is written.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use awrite::{awrite, sink::{Clock, TimestampSink}, AwriteBuf};
+///
+/// struct Fixed;
+///
+/// impl Clock for Fixed {
+///     fn now_millis(&self) -> u64 {
+///         1234
+///     }
+/// }
+///
+/// let mut buf = AwriteBuf::new([0u8; 32], TimestampSink::new(Vec::new(), Fixed, b' '));
+/// awrite!(buf, "boot\nready").unwrap();
+/// awrite!(buf, "!\n").unwrap();
+/// assert_eq!(buf.into_sink().into_inner(), b"1234 boot\n1234 ready!\n");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct TimestampSink<W, C> {
+    inner: W,
+    clock: C,
+    sep: u8,
+    bol: bool,
+}
+
+impl<W, C> TimestampSink<W, C> {
+    pub fn new(inner: W, clock: C, sep: u8) -> Self {
+        Self {
+            inner,
+            clock,
+            sep,
+            bol: true,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: ErrorType, C> ErrorType for TimestampSink<W, C> {
+    type Error = W::Error;
+}
+
+impl<W: Write, C: Clock> Write for TimestampSink<W, C> {
+    async fn write(&mut self, mut buf: &[u8]) -> Result<usize, Self::Error> {
+        let len = buf.len();
+        while !buf.is_empty() {
+            if self.bol {
+                let mut prefix = [0u8; 21];
+                let mut n = prefix.len() - 1;
+                prefix[n] = self.sep;
+                let mut t = self.clock.now_millis();
+                loop {
+                    n -= 1;
+                    prefix[n] = b'0' + (t % 10) as u8;
+                    t /= 10;
+                    if t == 0 {
+                        break;
+                    }
+                }
+                self.inner.write_all(&prefix[n..]).await?;
+                self.bol = false;
+            }
+            let line = buf
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(buf.len(), |i| i + 1);
+            self.inner.write_all(&buf[..line]).await?;
+            self.bol = buf[line - 1] == b'\n';
+            buf = &buf[line..];
+        }
+        Ok(len)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush().await
+    }
+}
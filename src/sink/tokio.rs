@@ -0,0 +1,48 @@
+use embedded_io::ErrorType;
+use embedded_io_async::Write;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Use a `tokio::io::AsyncWrite` as the sink
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use tokio::io::AsyncReadExt;
+/// use awrite::{awrite, sink::TokioSink, AwriteBuf};
+///
+/// let (tx, mut rx) = tokio::io::duplex(64);
+/// let mut buf = AwriteBuf::new([0u8; 16], TokioSink::new(tx));
+/// awrite!(buf, "{}", 42).unwrap();
+/// drop(buf);
+///
+/// let mut received = Vec::new();
+/// rx.read_to_end(&mut received).await.unwrap();
+/// assert_eq!(received, b"42");
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct TokioSink<W>(W);
+
+impl<W> TokioSink<W> {
+    pub fn new(inner: W) -> Self {
+        Self(inner)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+impl<W> ErrorType for TokioSink<W> {
+    type Error = std::io::Error;
+}
+
+impl<W: AsyncWrite + Unpin> Write for TokioSink<W> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0.write(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush().await
+    }
+}
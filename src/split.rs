@@ -0,0 +1,80 @@
+use core::cell::RefCell;
+use embedded_io::{ErrorType, SliceWriteError};
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Sync writing half of a shared [`AwriteBuf`], see [`AwriteBuf::split`]
+#[derive(Debug)]
+pub struct Writer<'a, T, U>(&'a RefCell<AwriteBuf<T, U>>);
+
+/// Async flushing half of a shared [`AwriteBuf`], see [`AwriteBuf::split`]
+#[derive(Debug)]
+pub struct Flusher<'a, T, U>(&'a RefCell<AwriteBuf<T, U>>);
+
+impl<T, U> AwriteBuf<T, U> {
+    /// Split a shared buffer into a sync [`Writer`] and an async [`Flusher`].
+    ///
+    /// Both halves borrow the `RefCell` only for the duration of a call.
+    /// While a flush is in flight the buffer is busy and writes fail with
+    /// `SliceWriteError::Full`.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::cell::RefCell;
+    /// use embedded_io::Write;
+    /// use awrite::AwriteBuf;
+    ///
+    /// let cell = RefCell::new(AwriteBuf::new([0u8; 16], Vec::<u8>::new()));
+    /// let (mut writer, mut flusher) = AwriteBuf::split(&cell);
+    ///
+    /// write!(writer, "produced {}", 1).unwrap();
+    /// flusher.flush().await.unwrap();
+    /// assert_eq!(cell.borrow().sink_ref(), b"produced 1");
+    /// # })
+    /// ```
+    pub fn split(cell: &RefCell<Self>) -> (Writer<'_, T, U>, Flusher<'_, T, U>) {
+        (Writer(cell), Flusher(cell))
+    }
+}
+
+impl<T, U: ErrorType> ErrorType for Writer<'_, T, U> {
+    type Error = Error<U::Error>;
+}
+
+impl<T: Scratch, U: ErrorType> embedded_io::Write for Writer<'_, T, U> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.0
+            .try_borrow_mut()
+            .map_or(Err(Error::Sync(SliceWriteError::Full)), |mut aw| {
+                embedded_io::Write::write(&mut *aw, buf)
+            })
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<T: Scratch, U> core::fmt::Write for Writer<'_, T, U> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0
+            .try_borrow_mut()
+            .map_or(Err(core::fmt::Error), |mut aw| {
+                core::fmt::Write::write_str(&mut *aw, s)
+            })
+    }
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> Flusher<'_, T, U> {
+    /// Flush the shared buffer, see `embedded_io_async::Write::flush()`.
+    // The borrow is held across the sink await on purpose: writers back off
+    // with `try_borrow_mut()` instead of panicking.
+    #[allow(clippy::await_holding_refcell_ref)]
+    pub async fn flush(&mut self) -> Result<(), Error<U::Error>> {
+        let mut buf = self
+            .0
+            .try_borrow_mut()
+            .or(Err(Error::Sync(SliceWriteError::Full)))?;
+        embedded_io_async::Write::flush(&mut *buf).await
+    }
+}
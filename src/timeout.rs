@@ -0,0 +1,86 @@
+use core::{future::Future, pin::pin, task::Poll};
+use embedded_hal_async::delay::DelayNs;
+
+use crate::{AwriteBuf, Error, Scratch};
+
+/// Error of [`AwriteBuf::flush_with_timeout`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlushTimeout<E> {
+    /// The flush did not complete in time
+    TimedOut,
+    Flush(E),
+}
+
+impl<E: embedded_io::Error> embedded_io::Error for FlushTimeout<E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            Self::TimedOut => embedded_io::ErrorKind::TimedOut,
+            Self::Flush(e) => e.kind(),
+        }
+    }
+}
+
+impl<T: Scratch, U: embedded_io_async::Write> AwriteBuf<T, U> {
+    /// Flush like [`AwriteBuf::flush_cancel_safe`], giving up after `ms` milliseconds.
+    ///
+    /// On timeout only the bytes the sink has not accepted stay buffered and a
+    /// later flush resumes with those. As there, the line prefix and indentation
+    /// do not apply.
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// use core::task::Poll;
+    /// use embedded_io::Write;
+    /// use awrite::{AwriteBuf, FlushTimeout};
+    ///
+    /// // Takes three bytes, then wedges
+    /// #[derive(Default)]
+    /// struct Wedged(Vec<u8>);
+    ///
+    /// impl embedded_io::ErrorType for Wedged {
+    ///     type Error = core::convert::Infallible;
+    /// }
+    ///
+    /// impl embedded_io_async::Write for Wedged {
+    ///     async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    ///         if !self.0.is_empty() {
+    ///             core::future::pending::<()>().await;
+    ///         }
+    ///         self.0.extend_from_slice(&buf[..3]);
+    ///         Ok(3)
+    ///     }
+    /// }
+    ///
+    /// // Expires right away
+    /// struct Timer;
+    ///
+    /// impl embedded_hal_async::delay::DelayNs for Timer {
+    ///     async fn delay_ns(&mut self, _ns: u32) {}
+    /// }
+    ///
+    /// let mut buf = AwriteBuf::new([0u8; 16], Wedged::default());
+    /// write!(buf, "stalled").unwrap();
+    /// assert_eq!(buf.flush_with_timeout(&mut Timer, 10).await, Err(FlushTimeout::TimedOut));
+    /// assert_eq!(buf.written(), b"lled");
+    /// assert_eq!(buf.sink_ref().0, b"sta");
+    /// # })
+    /// ```
+    pub async fn flush_with_timeout<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        ms: u32,
+    ) -> Result<(), FlushTimeout<Error<U::Error>>> {
+        let mut flush = pin!(self.flush_cancel_safe());
+        let mut timer = pin!(delay.delay_ms(ms));
+        core::future::poll_fn(|cx| {
+            if let Poll::Ready(ret) = flush.as_mut().poll(cx) {
+                return Poll::Ready(ret.map_err(FlushTimeout::Flush));
+            }
+            timer
+                .as_mut()
+                .poll(cx)
+                .map(|()| Err(FlushTimeout::TimedOut))
+        })
+        .await
+    }
+}
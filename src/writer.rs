@@ -0,0 +1,165 @@
+use embedded_io::{ErrorKind, ErrorType, Seek, SeekFrom};
+
+/// Async write buffer over a seekable sync writer scratch
+///
+/// Like [`crate::AwriteBuf`] but the scratch is any `embedded_io::Write + Seek`
+/// that exposes its contents via `AsRef<[u8]>`. The stream position of the scratch
+/// is the length of the pending data.
+///
+/// ```
+/// # tokio_test::block_on(async {
+/// use embedded_io::Write;
+/// use awrite::{awrite, AwriteBufW};
+///
+/// struct Cursor {
+///     buf: [u8; 16],
+///     pos: usize,
+/// }
+///
+/// impl embedded_io::ErrorType for Cursor {
+///     type Error = embedded_io::SliceWriteError;
+/// }
+///
+/// impl embedded_io::Write for Cursor {
+///     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+///         let n = (&mut self.buf[self.pos..]).write(buf)?;
+///         self.pos += n;
+///         Ok(n)
+///     }
+///
+///     fn flush(&mut self) -> Result<(), Self::Error> {
+///         Ok(())
+///     }
+/// }
+///
+/// impl embedded_io::Seek for Cursor {
+///     fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, Self::Error> {
+///         self.pos = match pos {
+///             embedded_io::SeekFrom::Start(pos) => pos as usize,
+///             embedded_io::SeekFrom::Current(0) => self.pos,
+///             _ => unimplemented!(),
+///         };
+///         Ok(self.pos as u64)
+///     }
+/// }
+///
+/// impl AsRef<[u8]> for Cursor {
+///     fn as_ref(&self) -> &[u8] {
+///         &self.buf
+///     }
+/// }
+///
+/// let cursor = Cursor { buf: [0; 16], pos: 0 };
+/// let mut buf = AwriteBufW::new(cursor, Vec::<u8>::new());
+///
+/// write!(buf, "a").unwrap();
+/// assert_eq!(awrite!(buf, "{}", 42), Ok(3));
+/// assert_eq!(buf.sink_ref(), b"a42");
+/// assert!(awrite!(buf, "this is too long!").is_err());
+/// # })
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, PartialOrd)]
+pub struct AwriteBufW<S, U> {
+    scratch: S,
+    sink: U,
+}
+
+impl<S, U> AwriteBufW<S, U> {
+    pub fn new(scratch: S, sink: U) -> Self {
+        Self { scratch, sink }
+    }
+
+    pub fn into_parts(self) -> (S, U) {
+        (self.scratch, self.sink)
+    }
+
+    pub fn sink_ref(&self) -> &U {
+        &self.sink
+    }
+
+    pub fn sink_mut(&mut self) -> &mut U {
+        &mut self.sink
+    }
+
+    pub fn scratch_ref(&self) -> &S {
+        &self.scratch
+    }
+}
+
+/// Error of an [`AwriteBufW`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum WriterError<S, U> {
+    /// The scratch writer failed
+    Scratch(S),
+    /// The async sink failed
+    Sink(U),
+}
+
+impl<S: embedded_io::Error, U: embedded_io::Error> embedded_io::Error for WriterError<S, U> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Scratch(e) => e.kind(),
+            Self::Sink(e) => e.kind(),
+        }
+    }
+}
+
+impl<S: ErrorType, U: ErrorType> ErrorType for AwriteBufW<S, U> {
+    type Error = WriterError<S::Error, U::Error>;
+}
+
+impl<S: embedded_io::Write, U: ErrorType> embedded_io::Write for AwriteBufW<S, U> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.scratch.write(buf).map_err(WriterError::Scratch)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<S: embedded_io::Write + AsRef<[u8]> + Seek, U: embedded_io_async::Write>
+    embedded_io_async::Write for AwriteBufW<S, U>
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        embedded_io::Write::write(self, buf)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        let pos = self
+            .scratch
+            .stream_position()
+            .map_err(WriterError::Scratch)? as usize;
+        self.sink
+            .write_all(&self.scratch.as_ref()[..pos])
+            .await
+            .map_err(WriterError::Sink)?;
+        self.scratch
+            .seek(SeekFrom::Start(0))
+            .map_err(WriterError::Scratch)?;
+        Ok(())
+    }
+}
+
+impl<S: embedded_io::Write + AsRef<[u8]> + Seek, U: embedded_io_async::Write> AwriteBufW<S, U> {
+    /// Format into the scratch and flush, yielding the number of bytes flushed.
+    ///
+    /// See [`crate::AwriteBuf::awrite_fmt`].
+    pub fn awrite_fmt(
+        &mut self,
+        args: core::fmt::Arguments<'_>,
+    ) -> impl core::future::Future<
+        Output = Result<usize, embedded_io::WriteFmtError<<Self as ErrorType>::Error>>,
+    > + '_ {
+        let ret = embedded_io::Write::write_fmt(self, args);
+        async move {
+            ret?;
+            let len = self
+                .scratch
+                .stream_position()
+                .map_err(WriterError::Scratch)? as usize;
+            embedded_io_async::Write::flush(self).await?;
+            Ok(len)
+        }
+    }
+}